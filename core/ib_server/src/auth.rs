@@ -8,7 +8,7 @@ use axum::{
     routing::get,
     Router,
 };
-use reqwest::header::AUTHORIZATION;
+use reqwest::header::{AUTHORIZATION, SEC_WEBSOCKET_PROTOCOL};
 use serde::Serialize;
 
 async fn ping_auth_backend(jwt: &str) -> Option<String> {
@@ -43,22 +43,57 @@ async fn ping_auth_backend(jwt: &str) -> Option<String> {
     Some(uid.clone())
 }
 
-pub async fn auth_middleware(mut req: Request, next: Next) -> Result<Response, StatusCode> {
+// the websocket subprotocol the client offers
+// next to its token, echoed back on upgrade
+pub const WS_PROTOCOL: &str = "bearer";
+
+async fn authenticate(mut req: Request, next: Next, jwt: String) -> Result<Response, StatusCode> {
+    match ping_auth_backend(&jwt).await {
+        Some(uid) => {
+            req.extensions_mut().insert(uid);
+            Ok(next.run(req).await)
+        }
+        None => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+pub async fn auth_middleware(req: Request, next: Next) -> Result<Response, StatusCode> {
     let Some(auth_header) = req.headers().get("Authorization") else {
         return Err(StatusCode::UNAUTHORIZED);
     };
 
-    let auth_header = auth_header.to_str().unwrap();
+    let Ok(auth_header) = auth_header.to_str() else {
+        return Err(StatusCode::UNAUTHORIZED);
+    };
 
     let Some(jwt) = auth_header.strip_prefix("Bearer ") else {
         return Err(StatusCode::UNAUTHORIZED);
     };
 
-    match ping_auth_backend(jwt).await {
-        Some(uid) => {
-            req.extensions_mut().insert(uid);
-            Ok(next.run(req).await)
-        }
-        None => Err(StatusCode::UNAUTHORIZED),
-    }
+    let jwt = jwt.to_string();
+    authenticate(req, next, jwt).await
+}
+
+pub async fn ws_auth_middleware(req: Request, next: Next) -> Result<Response, StatusCode> {
+    // browsers can't set headers on websockets, so the token
+    // is offered as a subprotocol after `WS_PROTOCOL`
+    let Some(protocols) = req.headers().get(SEC_WEBSOCKET_PROTOCOL) else {
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+
+    let Ok(protocols) = protocols.to_str() else {
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+
+    let Some(jwt) = protocols
+        .split(',')
+        .map(|p| p.trim())
+        .skip_while(|p| *p != WS_PROTOCOL)
+        .nth(1)
+    else {
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+
+    let jwt = jwt.to_string();
+    authenticate(req, next, jwt).await
 }
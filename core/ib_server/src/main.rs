@@ -1,6 +1,6 @@
 use std::{collections::HashMap, fs, path::Path, sync::Arc};
 
-use auth::auth_middleware;
+use auth::{auth_middleware, ws_auth_middleware};
 use axum::{
     extract::Query,
     routing::{get, post},
@@ -8,7 +8,7 @@ use axum::{
 };
 use rusqlite::Connection;
 use serde::Serialize;
-use sync::{create_file, delete_file, get_files};
+use sync::{create_file, delete_file, get_files, read_filename, read_module};
 use tokio::{net::TcpListener, sync::broadcast};
 use tower::ServiceBuilder;
 use tower_http::cors::{Any, CorsLayer};
@@ -28,6 +28,9 @@ struct Diagnostic {
     message: String,
     offset_start: usize,
     offset_end: usize,
+    // the imported file the error is in, None
+    // for errors in the analyzed file itself
+    file: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -42,8 +45,18 @@ pub struct IbFile {
     pub contents: String,
 }
 
-#[tokio::main]
-async fn main() {
+fn main() {
+    // connections are handled on the worker threads, which also run the evaluator
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .thread_stack_size(ibc::eval::evaluator::EVAL_STACK_SIZE)
+        .enable_all()
+        .build()
+        .unwrap();
+
+    runtime.block_on(serve());
+}
+
+async fn serve() {
     setup_db();
 
     let cors = CorsLayer::new()
@@ -62,7 +75,10 @@ async fn main() {
         .layer(axum::middleware::from_fn(auth_middleware));
 
     let app = Router::new()
-        .route("/ws", get(handle_ws))
+        .route(
+            "/ws",
+            get(handle_ws).layer(axum::middleware::from_fn(ws_auth_middleware)),
+        )
         .nest("/api", protected_router)
         .layer(ServiceBuilder::new().layer(cors))
         .layer(Extension(tx));
@@ -77,23 +93,27 @@ async fn diagnostics(
     query: Query<HashMap<String, String>>,
     body: String,
 ) -> Json<Vec<Diagnostic>> {
-    let result = ibc::analysis::analyze(body.clone());
-
     let id = match query.0.get("id") {
         Some(i) => i.clone(),
         None => return Json(Vec::new()),
     };
 
+    let name = read_filename(uid.clone(), id.clone());
+    let files = get_files(uid.clone());
+    let resolver = |module: &str| read_module(&files, module);
+    let result = ibc::analysis::analyze(body.clone(), name, &resolver);
+
     sync::sync_file(uid, id, body);
 
     let mut diagnostics: Vec<Diagnostic> = vec![];
-    let errors = result.errors.errors;
+    let errors = &result.errors;
 
-    for error in errors {
+    for error in errors.errors.iter() {
         let diagnostic = Diagnostic {
             message: error.kind.format(),
             offset_start: error.span.start.char_offset,
             offset_end: error.span.end.char_offset,
+            file: errors.file_name(error.span.file()).cloned(),
         };
 
         diagnostics.push(diagnostic)
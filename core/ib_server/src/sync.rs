@@ -117,3 +117,28 @@ pub fn get_files(uid: String) -> Vec<IbFile> {
 
     files
 }
+
+// `files` is read once per analysis, so every
+// import sees the same version of the user's files
+pub fn read_module(files: &[IbFile], module: &str) -> Option<String> {
+    // modules are imported by their filename,
+    // the .ib extension can be left out
+    let filename = format!("{}.ib", module);
+    files
+        .iter()
+        .find(|f| f.filename == module || f.filename == filename)
+        .map(|f| f.contents.clone())
+}
+
+pub fn read_filename(uid: String, id: String) -> Option<String> {
+    let db_file = match get_filename_uid(id) {
+        Some(f) => f,
+        None => return None,
+    };
+
+    if db_file.uid != uid {
+        return None;
+    }
+
+    Some(db_file.filename)
+}
@@ -1,15 +1,22 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
 use axum::{
-    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query,
+    },
     Extension,
 };
 use futures_util::{lock::Mutex, StreamExt};
 use ibc::eval::{evaluator, EvalIO};
 use serde::{Deserialize, Serialize, Serializer};
 
-use crate::{Broadcaster, Diagnostic};
+use crate::{
+    auth::WS_PROTOCOL,
+    sync::{get_files, read_filename, read_module},
+    Broadcaster, Diagnostic,
+};
 
 #[derive(Debug, Clone, Copy)]
 enum WebsocketMessageKind {
@@ -111,17 +118,21 @@ async fn send_await_resp(
     }
 }
 
-async fn execute(body: String, socket: Arc<Mutex<WebSocket>>) {
-    let result = ibc::analysis::analyze(body);
+async fn execute(uid: String, id: Option<String>, body: String, socket: Arc<Mutex<WebSocket>>) {
+    let name = id.and_then(|id| read_filename(uid.clone(), id));
+    let files = get_files(uid.clone());
+    let resolver = |module: &str| read_module(&files, module);
+    let result = ibc::analysis::analyze(body, name, &resolver);
 
     let mut diagnostics: Vec<Diagnostic> = vec![];
-    let errors = result.errors.errors;
+    let errors = &result.errors;
 
-    for error in errors {
+    for error in errors.errors.iter() {
         let diagnostic = Diagnostic {
             message: error.kind.format(),
             offset_start: error.span.start.char_offset,
             offset_end: error.span.end.char_offset,
+            file: errors.file_name(error.span.file()).cloned(),
         };
 
         diagnostics.push(diagnostic)
@@ -133,12 +144,17 @@ async fn execute(body: String, socket: Arc<Mutex<WebSocket>>) {
     };
 
     let mut io = WebSocketEvaluator { socket: socket.clone() };
-    evaluator::eval(&root, &mut io).await;
+    evaluator::eval(&root, &mut io, evaluator::DEFAULT_MAX_CALL_DEPTH).await;
 
     let _ = socket.lock().await.send(Message::Close(None)).await;
 }
 
-async fn handle_message(msg: String, socket: Arc<Mutex<WebSocket>>) {
+async fn handle_message(
+    uid: String,
+    id: Option<String>,
+    msg: String,
+    socket: Arc<Mutex<WebSocket>>,
+) {
     let msg: WebsocketMessage = match serde_json::from_str(&msg) {
         Ok(msg) => msg,
         Err(_) => {
@@ -149,7 +165,7 @@ async fn handle_message(msg: String, socket: Arc<Mutex<WebSocket>>) {
     match msg.kind {
         WebsocketMessageKind::Execute => {
             // start execution
-            execute(msg.payload, socket).await;
+            execute(uid, id, msg.payload, socket).await;
         }
         // inputs handled elsewhere
         WebsocketMessageKind::Input => {}
@@ -157,7 +173,12 @@ async fn handle_message(msg: String, socket: Arc<Mutex<WebSocket>>) {
     };
 }
 
-async fn handle_ws_socket(socket: WebSocket, tx: Broadcaster) {
+async fn handle_ws_socket(
+    socket: WebSocket,
+    uid: String,
+    id: Option<String>,
+    tx: Broadcaster,
+) {
     let socket = Arc::new(Mutex::new(socket));
     let mut rx = tx.subscribe();
 
@@ -170,7 +191,7 @@ async fn handle_ws_socket(socket: WebSocket, tx: Broadcaster) {
             } => {
                 match msg {
                     Ok(Message::Text(text)) => {
-                        handle_message(text.clone(), Arc::clone(&socket)).await;
+                        handle_message(uid.clone(), id.clone(), text.clone(), Arc::clone(&socket)).await;
 
                         if tx.send(text).is_err() {
                             break;
@@ -198,7 +219,14 @@ async fn handle_ws_socket(socket: WebSocket, tx: Broadcaster) {
 
 pub async fn handle_ws(
     ws: WebSocketUpgrade,
+    Extension(uid): Extension<String>,
     Extension(tx): Extension<Broadcaster>,
+    query: Query<HashMap<String, String>>,
 ) -> impl axum::response::IntoResponse {
-    ws.on_upgrade(|socket| handle_ws_socket(socket, tx))
+    // the id of the file that is run
+    let id = query.0.get("id").cloned();
+
+    // the browser drops the connection unless
+    // one of its protocols is echoed back
+    ws.protocols([WS_PROTOCOL]).on_upgrade(|socket| handle_ws_socket(socket, uid, id, tx))
 }
@@ -1,37 +1,92 @@
-use std::{cell::RefCell, rc::Rc, sync::Arc};
+use std::{cell::RefCell, cmp::Ordering, rc::Rc, sync::Arc};
 
 use crate::analysis::{
     error_bag::{ErrorBag, ErrorKind},
     operator::Operator,
     span::Span,
-    syntax::syntax_token::{SyntaxKind, SyntaxToken},
+    syntax::syntax_token::{SyntaxKind, SyntaxToken, TypeSyntax},
 };
 
 use super::{
-    bound_node::{BoundNode, BoundNodeKind, BoundParameter},
+    bound_node::BoundFieldInitializer,
+    bound_node::{BoundCaseArm, BoundNode, BoundNodeKind, BoundParameter},
     bound_scope::BoundScope,
-    symbols::VariableSymbol,
-    types::{get_type, TypeKind},
+    modules::ModuleLoader,
+    symbols::{
+        ClassSymbol, ConstantValue, FieldSymbol, FunctionSymbol, MethodSymbol, VariableSymbol,
+    },
+    types::{get_type, ClassType, TypeKind, TypeMethodRepresentation},
 };
 
-fn bind_module(
+// binding functions return None when the node couldn't be
+// bound, the error is reported before returning and `bind`
+// replaces the node with an error node so binding can continue
+
+fn infer_from_use(used: &TypeKind, used_as: &TypeKind, scope: &Rc<RefCell<BoundScope>>) {
+    // records how an untyped parameter is used,
+    // nothing happens for any other type
+    let inference = scope.borrow().inference();
+    inference.borrow_mut().unify(used, used_as);
+}
+
+pub fn bind_module(
+    imports: &Vec<SyntaxToken>,
     block: &SyntaxToken,
     scope: Rc<RefCell<BoundScope>>,
+    loader: &mut ModuleLoader,
     errors: &mut ErrorBag,
     span: Span,
-) -> Option<BoundNode> {
-    let block = match bind(block, scope, errors) {
+) -> (BoundNode, Vec<FunctionSymbol>) {
+    // imported functions are declared in the module's scope,
+    // its own declarations in a child scope so they can be exported
+    let mut imported = Vec::<BoundNode>::new();
+    for import in imports {
+        let SyntaxKind::ImportStatement { module } = &import.kind else {
+            // already reported by the parser
+            imported.push(BoundNode::error(import.span));
+            continue;
+        };
+
+        let (exports, module_node) = match loader.load(module, errors, import.span) {
+            Some(l) => l,
+            None => {
+                imported.push(BoundNode::error(import.span));
+                continue;
+            }
+        };
+
+        for function in exports {
+            let identifier = function.identifier.clone();
+            if !scope.borrow_mut().import_function(function) {
+                let kind = ErrorKind::CannotDeclareFunction(identifier);
+                errors.add(kind, import.span);
+                imported.push(BoundNode::error(import.span));
+            }
+        }
+
+        if let Some(n) = module_node {
+            imported.push(n);
+        }
+    }
+
+    let block_scope = Rc::new(RefCell::new(BoundScope::new(scope)));
+    let SyntaxKind::Scope { subtokens } = &block.kind else {
+        unreachable!()
+    };
+
+    let block = match bind_block(&subtokens, block_scope.clone(), false, errors, block.span) {
         Some(b) => b,
-        None => return None,
+        None => BoundNode::error(block.span),
     };
 
-    let node_type = block.node_type.clone();
     let kind = BoundNodeKind::Module {
+        imports: imported,
         block: Box::new(block),
     };
 
-    let node = BoundNode::new(kind, node_type, span);
-    Some(node)
+    let exports = block_scope.borrow().local_functions();
+    let node = BoundNode::new(kind, TypeKind::Void, span);
+    (node, exports)
 }
 
 fn bind_block(
@@ -48,11 +103,100 @@ fn bind_block(
         scope
     };
 
-    let mut bound = Vec::<BoundNode>::new();
+    // class names are declared first, so they can be
+    // used as types in any declaration of the block
+    let mut class_types = Vec::<Option<ClassType>>::new();
     for child in children {
-        let bound_child = match bind(child, scope_ref.clone(), errors) {
-            Some(n) => n,
-            None => return None,
+        let SyntaxKind::ClassDeclaration { identifier, .. } = &child.kind else {
+            class_types.push(None);
+            continue;
+        };
+
+        let class_type = declare_class(identifier.clone(), scope_ref.clone(), errors, child.span);
+        class_types.push(class_type);
+    }
+
+    // parents are resolved before any type is bound, so
+    // subclasses are assignable wherever a parent is used
+    resolve_class_parents(children, &mut class_types, scope_ref.clone(), errors);
+
+    // functions and class members are declared before any body is
+    // bound, so they can be called recursively or before their declaration
+    let mut declared = Vec::<Option<HoistedDeclaration>>::new();
+    for child in children {
+        let hoisted = match &child.kind {
+            SyntaxKind::FunctionDeclaration {
+                identifier,
+                parameters,
+                return_type,
+                ..
+            } => {
+                let declared_function = declare_function(
+                    identifier.clone(),
+                    parameters,
+                    return_type,
+                    scope_ref.clone(),
+                    errors,
+                    child.span,
+                );
+
+                match declared_function {
+                    Some(f) => Some(HoistedDeclaration::Function(f)),
+                    None => None,
+                }
+            }
+            _ => None,
+        };
+
+        declared.push(hoisted);
+    }
+
+    // subclasses inherit the members of their parent,
+    // so parents are declared first
+    let mut class_order: Vec<usize> = (0..children.len()).collect();
+    class_order.sort_by_key(|i| match &class_types[*i] {
+        Some(c) => c.ancestors.len(),
+        None => 0,
+    });
+
+    for i in class_order {
+        let (
+            SyntaxKind::ClassDeclaration {
+                parent, members, ..
+            },
+            Some(class_type),
+        ) = (&children[i].kind, class_types[i].clone())
+        else {
+            continue;
+        };
+
+        let declared_class =
+            declare_class_members(class_type, parent, members, scope_ref.clone(), errors);
+        declared[i] = Some(HoistedDeclaration::Class(declared_class));
+    }
+
+    let mut bound = Vec::<BoundNode>::new();
+    for (child, hoisted) in children.iter().zip(declared) {
+        let bound_child = match (&child.kind, hoisted) {
+            (
+                SyntaxKind::FunctionDeclaration { body, .. },
+                Some(HoistedDeclaration::Function(declared_function)),
+            ) => match bind_function_body(declared_function, &body, errors, child.span) {
+                Some(n) => n,
+                None => BoundNode::error(child.span),
+            },
+            (
+                SyntaxKind::ClassDeclaration { members, .. },
+                Some(HoistedDeclaration::Class(declared_class)),
+            ) => match bind_class_body(declared_class, &members, errors, child.span) {
+                Some(n) if !scope_ref.borrow().is_in_function() => n,
+                _ => BoundNode::error(child.span),
+            },
+            (SyntaxKind::FunctionDeclaration { .. } | SyntaxKind::ClassDeclaration { .. }, _) => {
+                // the declaration was already reported
+                BoundNode::error(child.span)
+            }
+            _ => bind(child, scope_ref.clone(), errors),
         };
 
         bound.push(bound_child);
@@ -72,10 +216,19 @@ fn bind_output_statement(
     errors: &mut ErrorBag,
     span: Span,
 ) -> Option<BoundNode> {
-    let expr = match bind(expr, scope, errors) {
-        Some(expr) => expr,
-        None => return None,
-    };
+    let expr = bind(expr, scope, errors);
+
+    // objects have no printable form, the same as
+    // in string interpolation
+    match &expr.node_type {
+        TypeKind::Void | TypeKind::Infer(_) | TypeKind::Error => {}
+        t if t.is_primitive() => {}
+        t => {
+            let kind = ErrorKind::CannotOutputType(t.clone());
+            errors.add(kind, expr.span);
+            return None;
+        }
+    }
 
     let kind = BoundNodeKind::OutputStatement {
         expr: Box::new(expr),
@@ -93,12 +246,17 @@ fn bind_return_statement(
 ) -> Option<BoundNode> {
     let (ret_type, expr) = match ret_expr {
         Some(ret_expr) => {
-            let ret_expr = match bind(ret_expr, scope.clone(), errors) {
-                Some(e) => e,
-                None => return None,
-            };
+            let ret_expr = bind(ret_expr, scope.clone(), errors);
 
             let ret_type = ret_expr.node_type.clone();
+            // an inferred return type is found from the
+            // function's returns once it has been bound
+            if let Some(func_ret_type) = scope.borrow().ret_type() {
+                if !func_ret_type.is_inferred() {
+                    infer_from_use(&ret_type, &func_ret_type, &scope);
+                }
+            }
+
             let ret_expr = Some(Box::new(ret_expr));
             (ret_type, ret_expr)
         }
@@ -110,6 +268,25 @@ fn bind_return_statement(
     Some(node)
 }
 
+fn bind_condition(
+    condition: &SyntaxToken,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+) -> BoundNode {
+    let condition = bind(condition, scope.clone(), errors);
+
+    let node_type = condition.node_type.clone();
+    infer_from_use(&node_type, &TypeKind::Boolean, &scope);
+
+    if !TypeKind::Boolean.is_assignable_from(&node_type) {
+        let span = condition.span;
+        errors.add(ErrorKind::ConditionMustBeBoolean(node_type), span);
+        return BoundNode::error(span);
+    }
+
+    condition
+}
+
 fn bind_if_statement(
     condition: &SyntaxToken,
     next: &SyntaxToken,
@@ -118,29 +295,11 @@ fn bind_if_statement(
     errors: &mut ErrorBag,
     span: Span,
 ) -> Option<BoundNode> {
-    let condition = match bind(condition, scope.clone(), errors) {
-        Some(cond) => cond,
-        None => return None,
-    };
-
-    if condition.node_type != TypeKind::Boolean {
-        errors.add(
-            ErrorKind::ConditionMustBeBoolean(condition.node_type),
-            condition.span,
-        );
-        return None;
-    }
-
-    let block = match bind(next, scope.clone(), errors) {
-        Some(n) => n,
-        None => return None,
-    };
+    let condition = bind_condition(condition, scope.clone(), errors);
+    let block = bind(next, scope.clone(), errors);
 
     let else_block = match else_next {
-        Some(e) => match bind(e, scope, errors) {
-            Some(e) => Some(Box::new(e)),
-            None => return None,
-        },
+        Some(e) => Some(Box::new(bind(e, scope, errors))),
         None => None,
     };
 
@@ -154,91 +313,767 @@ fn bind_if_statement(
     Some(node)
 }
 
-fn bind_function_declaration(
-    identifier: String,
-    params: &Vec<SyntaxToken>,
-    ret_type: &Option<String>,
-    block: &SyntaxToken,
+fn bind_case_statement(
+    expr: &SyntaxToken,
+    arms: &Vec<SyntaxToken>,
+    otherwise: Option<&SyntaxToken>,
     scope: Rc<RefCell<BoundScope>>,
     errors: &mut ErrorBag,
     span: Span,
 ) -> Option<BoundNode> {
-    let func_scope = BoundScope::new(scope.clone());
-    let func_scope_ref = Rc::new(RefCell::new(func_scope));
+    let expr = bind(expr, scope.clone(), errors);
 
-    let params = bind_params(params, func_scope_ref.clone(), errors);
-    let params = match params {
-        Some(p) => p,
-        None => return None,
+    // arms are compared with ==, which
+    // only works on primitive values
+    let expr_type = expr.node_type.clone();
+    let comparable = expr_type.is_numeric()
+        || expr_type == TypeKind::Boolean
+        || expr_type == TypeKind::String
+        || expr_type.is_inferred()
+        || expr_type.is_error();
+
+    if !comparable {
+        let kind = ErrorKind::CaseNotDefinedOnType(expr_type.clone());
+        errors.add(kind, expr.span);
+    }
+
+    // the arms are still bound if the expression
+    // is broken, so errors in them are reported too
+    let mut bound_arms = Vec::<BoundCaseArm>::new();
+    let mut handled = Vec::<ConstantValue>::new();
+    for arm in arms.iter() {
+        let SyntaxKind::CaseArm { value, body } = &arm.kind else {
+            unreachable!()
+        };
+
+        let mut value = bind(value, scope.clone(), errors);
+        let value_type = value.node_type.clone();
+        infer_from_use(&value_type, &expr_type, &scope);
+
+        if comparable && !expr_type.is_assignable_from(&value_type) {
+            let kind = ErrorKind::CaseArmMismatchedTypes {
+                expected: expr_type.clone(),
+                found: value_type,
+            };
+
+            errors.add(kind, value.span);
+            value = BoundNode::error(value.span);
+        } else if let Some(constant) = fold_constant(&value) {
+            // ints are widened when matching reals,
+            // so 2 and 2.0 are the same arm
+            let constant = match (constant, &expr_type) {
+                (ConstantValue::Int(v), TypeKind::Real) => ConstantValue::Real(v as f64),
+                (constant, _) => constant,
+            };
+
+            if handled.contains(&constant) {
+                errors.add(ErrorKind::DuplicateCaseArm, value.span);
+                value = BoundNode::error(value.span);
+            } else {
+                handled.push(constant);
+            }
+        }
+
+        let block = bind(body, scope.clone(), errors);
+        bound_arms.push(BoundCaseArm {
+            value: value,
+            block: block,
+        });
+    }
+
+    let otherwise = match otherwise {
+        Some(o) => Some(Box::new(bind(o, scope, errors))),
+        None => None,
     };
 
-    let ret_type = match ret_type {
-        Some(t) => t,
-        None => "Void",
+    if !comparable {
+        return None;
     }
-    .to_string();
 
-    let ret_type = match get_type(ret_type, None, &span, errors) {
-        Some(t) => t,
-        None => return None,
+    let kind = BoundNodeKind::CaseStatement {
+        expr: Box::new(expr),
+        arms: bound_arms,
+        otherwise: otherwise,
     };
 
+    let node = BoundNode::new(kind, TypeKind::Void, span);
+    Some(node)
+}
+
+enum HoistedDeclaration {
+    Function(DeclaredFunction),
+    Class(DeclaredClass),
+}
+
+struct DeclaredFunction {
+    symbol: FunctionSymbol,
+    scope: Rc<RefCell<BoundScope>>,
+    // a parameter or the return type couldn't be bound
+    poisoned: bool,
+}
+
+fn infer_return_type(
+    identifier: String,
+    scope: &Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> TypeKind {
+    // a type variable while the program is first bound,
+    // the type inferred from its returns the second time
+    let inference = scope.borrow().inference();
+    let ret_type = inference.borrow_mut().return_type(identifier, &span);
+
+    match ret_type {
+        Ok(t) => t,
+        Err(conflicts) => {
+            for (kind, span) in conflicts {
+                errors.add(kind, span);
+            }
+
+            TypeKind::Error
+        }
+    }
+}
+
+fn bind_signature(
+    identifier: &String,
+    params: &Vec<SyntaxToken>,
+    ret_type: &Option<TypeSyntax>,
+    infer_ret_type: bool,
+    func_scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> (Vec<BoundParameter>, TypeKind) {
+    let params = bind_params(params, func_scope.clone(), errors);
+
+    let ret_type = match ret_type {
+        Some(t) => match get_type(t, &func_scope.borrow(), errors) {
+            Some(t) => t,
+            None => TypeKind::Error,
+        },
+        None if infer_ret_type => infer_return_type(identifier.clone(), &func_scope, errors, span),
+        None => TypeKind::Void,
+    };
+
+    func_scope.borrow_mut().set_ret_type(ret_type.clone());
+    (params, ret_type)
+}
+
+fn declare_function(
+    identifier: String,
+    params: &Vec<SyntaxToken>,
+    ret_type: &Option<TypeSyntax>,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<DeclaredFunction> {
+    let func_scope = BoundScope::new_function(scope.clone());
+    let func_scope_ref = Rc::new(RefCell::new(func_scope));
+
+    let num_errors = errors.errors.len();
+    let (params, ret_type) = bind_signature(
+        &identifier,
+        params,
+        ret_type,
+        true,
+        func_scope_ref.clone(),
+        errors,
+        span,
+    );
+
+    // the function is still declared, so calls
+    // to it don't report any further errors
+    let symbol = scope
+        .borrow_mut()
+        .declare_function(identifier.clone(), params, ret_type);
+
+    let symbol = match symbol {
+        Some(s) => s,
+        None => {
+            let kind = ErrorKind::CannotDeclareFunction(identifier);
+            errors.add(kind, span);
+            return None;
+        }
+    };
+
+    let declared = DeclaredFunction {
+        symbol: symbol,
+        scope: func_scope_ref,
+        poisoned: errors.errors.len() != num_errors,
+    };
+
+    Some(declared)
+}
+
+fn bind_function_body(
+    declared: DeclaredFunction,
+    block: &SyntaxToken,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<BoundNode> {
     let block_span = block.span.clone();
     let SyntaxKind::Scope { subtokens } = &block.kind else {
         return None;
     };
 
-    let block = match bind_block(&subtokens, func_scope_ref, false, errors, block_span) {
+    let block = match bind_block(&subtokens, declared.scope, false, errors, block_span) {
         Some(b) => b,
         None => return None,
     };
 
-    let symbol =
-        scope
-            .borrow_mut()
-            .declare_function(identifier.clone(), params.clone(), ret_type.clone());
+    if declared.poisoned {
+        return None;
+    }
 
-    let kind = match symbol {
-        Some(s) => BoundNodeKind::FunctionDeclaration {
-            symbol: s,
-            block: Arc::new(block),
-        },
+    let kind = BoundNodeKind::FunctionDeclaration {
+        symbol: declared.symbol,
+        block: Arc::new(block),
+    };
+
+    let node = BoundNode::new(kind, TypeKind::Void, span);
+    Some(node)
+}
+
+fn bind_function_declaration(
+    identifier: String,
+    params: &Vec<SyntaxToken>,
+    ret_type: &Option<TypeSyntax>,
+    block: &SyntaxToken,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<BoundNode> {
+    let declared = match declare_function(identifier, params, ret_type, scope, errors, span) {
+        Some(d) => d,
+        None => return None,
+    };
+
+    bind_function_body(declared, block, errors, span)
+}
+
+enum DeclaredMember {
+    Field(FieldSymbol),
+    Method(DeclaredFunction),
+}
+
+struct DeclaredClass {
+    symbol: ClassSymbol,
+    scope: Rc<RefCell<BoundScope>>,
+    // one entry per member of the declaration, None
+    // for members that couldn't be declared
+    members: Vec<Option<DeclaredMember>>,
+    // a member or the parent couldn't be bound
+    poisoned: bool,
+}
+
+fn declare_class(
+    identifier: String,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<ClassType> {
+    // methods could outlive the frame of the function
+    // they're declared in, along with its variables.
+    // the class is still declared so its uses aren't reported
+    if scope.borrow().is_in_function() {
+        let kind = ErrorKind::ClassInsideFunction(identifier.clone());
+        errors.add(kind, span);
+    }
+
+    let class_type = scope.borrow_mut().declare_class(identifier.clone());
+    if class_type.is_none() {
+        let kind = ErrorKind::CannotDeclareClass(identifier);
+        errors.add(kind, span);
+    }
+
+    class_type
+}
+
+fn resolve_class_parent(
+    class_type: ClassType,
+    parent: &String,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> ClassType {
+    let parent = match scope.borrow().get_class(parent.clone()) {
+        Some(p) => p,
         None => {
-            let kind = ErrorKind::CannotDeclareFunction(identifier.clone());
+            // the class is still declared, just without a parent
+            let kind = ErrorKind::UndefinedType(parent.clone());
             errors.add(kind, span);
-            return None;
+            return class_type;
+        }
+    };
+
+    if parent.class_id == class_type.class_id {
+        let kind = ErrorKind::CyclicInheritance(class_type.identifier.clone());
+        errors.add(kind, span);
+        return class_type;
+    }
+
+    scope.borrow_mut().set_class_parent(&class_type, &parent)
+}
+
+fn resolve_class_parents(
+    children: &Vec<SyntaxToken>,
+    class_types: &mut Vec<Option<ClassType>>,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+) {
+    let parent_of = |i: usize| match &children[i].kind {
+        SyntaxKind::ClassDeclaration { parent, .. } => parent.as_ref(),
+        _ => None,
+    };
+
+    let mut pending: Vec<usize> = (0..children.len())
+        .filter(|i| class_types[*i].is_some() && parent_of(*i).is_some())
+        .collect();
+
+    // a class can extend one declared later in the block, so a
+    // class is only resolved once its parent has been resolved
+    while pending.len() > 0 {
+        let ready = pending.iter().position(|i| {
+            let parent = parent_of(*i).unwrap();
+            !pending
+                .iter()
+                .any(|p| &class_types[*p].as_ref().unwrap().identifier == parent)
+        });
+
+        let Some(position) = ready else {
+            for i in pending {
+                let identifier = class_types[i].as_ref().unwrap().identifier.clone();
+                let kind = ErrorKind::CyclicInheritance(identifier);
+                errors.add(kind, children[i].span);
+            }
+
+            return;
+        };
+
+        let i = pending.remove(position);
+        let class_type = class_types[i].take().unwrap();
+        let parent = parent_of(i).unwrap();
+        let span = children[i].span;
+
+        class_types[i] = Some(resolve_class_parent(
+            class_type,
+            parent,
+            scope.clone(),
+            errors,
+            span,
+        ));
+    }
+}
+
+fn unwrap_class_member(member: &SyntaxToken) -> (&SyntaxToken, bool) {
+    match &member.kind {
+        SyntaxKind::PrivateMember { member } => (member, true),
+        _ => (member, false),
+    }
+}
+
+fn matches_overridden(method: &FunctionSymbol, overridden: &FunctionSymbol) -> bool {
+    // parameters must match exactly, the return
+    // type can be narrowed to a subclass
+    let params_match = method.parameters.len() == overridden.parameters.len()
+        && method
+            .parameters
+            .iter()
+            .zip(overridden.parameters.iter())
+            .all(|(m, o)| m.param_type == o.param_type && m.is_ref == o.is_ref);
+
+    let ret_matches = match (&overridden.ret_type, &method.ret_type) {
+        (TypeKind::Class(o), TypeKind::Class(m)) => m.is_subclass_of(o),
+        (o, m) => o == m,
+    };
+
+    params_match && ret_matches
+}
+
+fn declare_class_method(
+    identifier: String,
+    params: &Vec<SyntaxToken>,
+    ret_type: &Option<TypeSyntax>,
+    class_type: &ClassType,
+    is_private: bool,
+    is_constructor: bool,
+    class_scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> (MethodSymbol, DeclaredFunction) {
+    // the instance is assigned to `this` in the method's
+    // frame, before the parameters
+    let mut func_scope = BoundScope::new_function(class_scope.clone());
+    let this_type = TypeKind::Class(class_type.clone());
+    let this = func_scope.declare_variable("this".to_string(), this_type);
+    let func_scope_ref = Rc::new(RefCell::new(func_scope));
+
+    // constructors never return a value
+    let num_errors = errors.errors.len();
+    let (params, ret_type) = bind_signature(
+        &identifier,
+        params,
+        ret_type,
+        !is_constructor,
+        func_scope_ref.clone(),
+        errors,
+        span,
+    );
+
+    let function = class_scope
+        .borrow()
+        .alloc_method(identifier, params, ret_type);
+
+    let method = MethodSymbol {
+        function: function.clone(),
+        this: this,
+        owner_id: class_type.class_id,
+        is_private: is_private,
+    };
+
+    let declared = DeclaredFunction {
+        symbol: function,
+        scope: func_scope_ref,
+        poisoned: errors.errors.len() != num_errors,
+    };
+
+    (method, declared)
+}
+
+fn declare_class_members(
+    class_type: ClassType,
+    parent: &Option<String>,
+    members: &Vec<SyntaxToken>,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+) -> DeclaredClass {
+    let class_scope = BoundScope::new_class(scope, class_type.class_id);
+    let class_scope = Rc::new(RefCell::new(class_scope));
+    let class_id = class_type.class_id;
+    let num_errors = errors.errors.len();
+
+    // the parent's members are inherited, its
+    // constructor too unless one is declared
+    let parent_type = class_scope.borrow().get_class_symbol(&class_type).parent;
+    let unresolved_parent = parent.is_some() && parent_type.is_none();
+
+    let mut symbol = match &parent_type {
+        Some(p) => {
+            let parent_symbol = class_scope.borrow().get_class_symbol(p);
+            ClassSymbol {
+                class_type: class_type.clone(),
+                parent: parent_type.clone(),
+                fields: parent_symbol.fields,
+                methods: parent_symbol.methods,
+                constructor: parent_symbol.constructor,
+            }
+        }
+        None => ClassSymbol {
+            class_type: class_type.clone(),
+            parent: None,
+            fields: Vec::new(),
+            methods: Vec::new(),
+            constructor: None,
+        },
+    };
+
+    let mut declared_members = Vec::<Option<DeclaredMember>>::new();
+    for member in members {
+        let (member, is_private) = unwrap_class_member(member);
+        let member_identifier = match &member.kind {
+            SyntaxKind::VariableDeclaration { identifier, .. }
+            | SyntaxKind::FunctionDeclaration { identifier, .. } => identifier.as_str(),
+            SyntaxKind::ConstructorDeclaration { .. } => "constructor",
+            // already reported by the parser
+            _ => {
+                declared_members.push(None);
+                continue;
+            }
+        };
+
+        // inherited methods can be overridden, any
+        // other member can only be declared once
+        let is_duplicate = match &member.kind {
+            SyntaxKind::ConstructorDeclaration { .. } => match &symbol.constructor {
+                Some(c) => c.owner_id == class_id,
+                None => false,
+            },
+            SyntaxKind::FunctionDeclaration { .. } => match symbol.get_method(member_identifier) {
+                Some(m) => m.owner_id == class_id,
+                None => symbol.get_field(member_identifier).is_some(),
+            },
+            _ => symbol.has_member(member_identifier),
+        };
+
+        if is_duplicate {
+            let kind = ErrorKind::DuplicateClassMember {
+                class: class_type.identifier.clone(),
+                member: member_identifier.to_string(),
+            };
+
+            errors.add(kind, member.span);
+            declared_members.push(None);
+            continue;
+        }
+
+        let declared = match &member.kind {
+            SyntaxKind::VariableDeclaration {
+                identifier,
+                type_annotation,
+                ..
+            } => {
+                let field_type = match get_type(type_annotation, &class_scope.borrow(), errors) {
+                    Some(t) => t,
+                    None => TypeKind::Error,
+                };
+
+                let field = class_scope.borrow().alloc_field(
+                    identifier.clone(),
+                    field_type,
+                    class_id,
+                    is_private,
+                );
+
+                symbol.fields.push(field.clone());
+                DeclaredMember::Field(field)
+            }
+            SyntaxKind::FunctionDeclaration {
+                identifier,
+                parameters,
+                return_type,
+                ..
+            } => {
+                let (method, declared) = declare_class_method(
+                    identifier.clone(),
+                    parameters,
+                    return_type,
+                    &class_type,
+                    is_private,
+                    false,
+                    class_scope.clone(),
+                    errors,
+                    member.span,
+                );
+
+                let overridden = symbol
+                    .methods
+                    .iter()
+                    .position(|m| &m.function.identifier == identifier);
+
+                match overridden {
+                    Some(i) => {
+                        if !declared.poisoned
+                            && !matches_overridden(&method.function, &symbol.methods[i].function)
+                        {
+                            let kind = ErrorKind::MismatchedOverride {
+                                class: class_type.identifier.clone(),
+                                method: identifier.clone(),
+                            };
+
+                            errors.add(kind, member.span);
+                        }
+
+                        symbol.methods[i] = method;
+                    }
+                    None => symbol.methods.push(method),
+                }
+
+                DeclaredMember::Method(declared)
+            }
+            SyntaxKind::ConstructorDeclaration { parameters, .. } => {
+                let (method, declared) = declare_class_method(
+                    "constructor".to_string(),
+                    parameters,
+                    &None,
+                    &class_type,
+                    is_private,
+                    true,
+                    class_scope.clone(),
+                    errors,
+                    member.span,
+                );
+
+                symbol.constructor = Some(method);
+                DeclaredMember::Method(declared)
+            }
+            _ => unreachable!(),
+        };
+
+        declared_members.push(Some(declared));
+    }
+
+    class_scope.borrow().define_class(symbol.clone());
+
+    DeclaredClass {
+        symbol: symbol,
+        scope: class_scope,
+        members: declared_members,
+        poisoned: unresolved_parent || errors.errors.len() != num_errors,
+    }
+}
+
+fn bind_class_body(
+    declared: DeclaredClass,
+    members: &Vec<SyntaxToken>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<BoundNode> {
+    let mut initializers = Vec::<BoundFieldInitializer>::new();
+    let mut methods = Vec::<BoundNode>::new();
+
+    for (member, declared_member) in members.iter().zip(declared.members) {
+        let (member, _) = unwrap_class_member(member);
+        match (&member.kind, declared_member) {
+            (
+                SyntaxKind::VariableDeclaration {
+                    value: Some(value), ..
+                },
+                Some(DeclaredMember::Field(field)),
+            ) => {
+                // initializers are evaluated whenever an
+                // instance is created, before the constructor
+                let mut value = bind(value, declared.scope.clone(), errors);
+                infer_from_use(&value.node_type, &field.field_type, &declared.scope);
+
+                if !field.field_type.is_assignable_from(&value.node_type) {
+                    let kind = ErrorKind::AssignMismatchedTypes {
+                        expected: field.field_type.clone(),
+                        found: value.node_type,
+                    };
+
+                    errors.add(kind, member.span);
+                    value = BoundNode::error(value.span);
+                }
+
+                let initializer = BoundFieldInitializer {
+                    field: field,
+                    value: value,
+                };
+
+                initializers.push(initializer);
+            }
+            (
+                SyntaxKind::FunctionDeclaration { body, .. }
+                | SyntaxKind::ConstructorDeclaration { body, .. },
+                Some(DeclaredMember::Method(declared_method)),
+            ) => {
+                let method = match bind_function_body(declared_method, &body, errors, member.span) {
+                    Some(m) => m,
+                    None => BoundNode::error(member.span),
+                };
+
+                methods.push(method);
+            }
+            (SyntaxKind::VariableDeclaration { .. }, Some(_)) => {}
+            // already reported
+            _ => methods.push(BoundNode::error(member.span)),
         }
+    }
+
+    if declared.poisoned {
+        return None;
+    }
+
+    let kind = BoundNodeKind::ClassDeclaration {
+        symbol: declared.symbol,
+        initializers: Arc::new(initializers),
+        methods: methods,
     };
 
     let node = BoundNode::new(kind, TypeKind::Void, span);
     Some(node)
 }
 
+fn bind_class_declaration(
+    identifier: String,
+    parent: &Option<String>,
+    members: &Vec<SyntaxToken>,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<BoundNode> {
+    let in_function = scope.borrow().is_in_function();
+    let mut class_type = match declare_class(identifier, scope.clone(), errors, span) {
+        Some(c) => c,
+        None => return None,
+    };
+
+    if let Some(p) = parent {
+        class_type = resolve_class_parent(class_type, p, scope.clone(), errors, span);
+    }
+
+    let declared = declare_class_members(class_type, parent, members, scope, errors);
+    let node = bind_class_body(declared, members, errors, span);
+    if in_function {
+        return None;
+    }
+
+    node
+}
+
+fn bind_loop_bound(
+    bound: &SyntaxToken,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+) -> BoundNode {
+    let bound = bind(bound, scope.clone(), errors);
+    infer_from_use(&bound.node_type, &TypeKind::Int, &scope);
+
+    if !TypeKind::Int.is_assignable_from(&bound.node_type) {
+        let kind = ErrorKind::LoopBoundMustBeInt(bound.node_type);
+        errors.add(kind, bound.span);
+        return BoundNode::error(bound.span);
+    }
+
+    bound
+}
+
 fn bind_for_statement(
     identifier: String,
-    lower_bound: usize,
-    upper_bound: usize,
+    lower_bound: &SyntaxToken,
+    upper_bound: &SyntaxToken,
+    step: Option<&SyntaxToken>,
     body: &SyntaxToken,
     scope: Rc<RefCell<BoundScope>>,
     errors: &mut ErrorBag,
     span: Span,
 ) -> Option<BoundNode> {
-    let mut loop_scope = BoundScope::new(scope);
+    // bounds are evaluated before the
+    // iterator is declared, so bind them
+    // in the enclosing scope
+    let lower_bound = bind_loop_bound(lower_bound, scope.clone(), errors);
+    let upper_bound = bind_loop_bound(upper_bound, scope.clone(), errors);
+
+    let step = match step {
+        Some(s) => {
+            let step = bind_loop_bound(s, scope.clone(), errors);
+            if let BoundNodeKind::NumberLiteral(0) = step.kind {
+                errors.add(ErrorKind::LoopStepCannotBeZero, step.span);
+                Some(Box::new(BoundNode::error(step.span)))
+            } else {
+                Some(Box::new(step))
+            }
+        }
+        None => None,
+    };
+
+    let mut loop_scope = BoundScope::new_loop(scope);
     let iterator = match loop_scope.assign_variable(identifier, TypeKind::Int) {
-        Some(s) => s,
-        None => return None,
+        Ok(s) => s,
+        Err(kind) => {
+            errors.add(kind, span);
+            return None;
+        }
     };
 
     let loop_scope = Rc::new(RefCell::new(loop_scope));
-    let body = match bind(body, loop_scope, errors) {
-        Some(b) => b,
-        None => return None,
-    };
+    let body = bind(body, loop_scope, errors);
 
     let kind = BoundNodeKind::ForLoop {
         iterator: iterator,
-        lower_bound: lower_bound,
-        upper_bound: upper_bound,
+        lower_bound: Box::new(lower_bound),
+        upper_bound: Box::new(upper_bound),
+        step: step,
         block: Arc::new(body),
     };
 
@@ -253,22 +1088,10 @@ fn bind_while_statement(
     errors: &mut ErrorBag,
     span: Span,
 ) -> Option<BoundNode> {
-    let expr = match bind(expr, scope.clone(), errors) {
-        Some(e) => e,
-        None => return None,
-    };
+    let expr = bind_condition(expr, scope.clone(), errors);
 
-    let expr_type = expr.node_type.clone();
-    if expr_type != TypeKind::Boolean {
-        let kind = ErrorKind::ConditionMustBeBoolean(expr_type);
-        errors.add(kind, expr.span);
-        return None;
-    }
-
-    let body = match bind(body, scope, errors) {
-        Some(b) => b,
-        None => return None,
-    };
+    let loop_scope = BoundScope::new_loop(scope);
+    let body = bind(body, Rc::new(RefCell::new(loop_scope)), errors);
 
     let kind = BoundNodeKind::WhileLoop {
         expr: Box::new(expr),
@@ -279,47 +1102,104 @@ fn bind_while_statement(
     Some(node)
 }
 
+fn bind_break_statement(
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<BoundNode> {
+    if !scope.borrow().is_in_loop() {
+        errors.add(ErrorKind::BreakOutsideLoop, span);
+        return None;
+    }
+
+    let node = BoundNode::new(BoundNodeKind::BreakStatement, TypeKind::Void, span);
+    Some(node)
+}
+
+fn bind_continue_statement(
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<BoundNode> {
+    if !scope.borrow().is_in_loop() {
+        errors.add(ErrorKind::ContinueOutsideLoop, span);
+        return None;
+    }
+
+    let node = BoundNode::new(BoundNodeKind::ContinueStatement, TypeKind::Void, span);
+    Some(node)
+}
+
+fn infer_param_type(
+    identifier: String,
+    scope: &Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> TypeKind {
+    // a type variable while the program is first bound,
+    // the type inferred from its uses the second time
+    let inference = scope.borrow().inference();
+    let param_type = inference.borrow_mut().param_type(identifier, &span);
+
+    match param_type {
+        Ok(t) => t,
+        Err(kind) => {
+            errors.add(kind, span);
+            TypeKind::Error
+        }
+    }
+}
+
 fn bind_params(
     params: &Vec<SyntaxToken>,
     scope: Rc<RefCell<BoundScope>>,
     errors: &mut ErrorBag,
-) -> Option<Vec<BoundParameter>> {
+) -> Vec<BoundParameter> {
     let mut parameters: Vec<BoundParameter> = Vec::new();
     for param in params {
         let span = param.span.clone();
         let SyntaxKind::Parameter {
             identifier,
             type_annotation,
+            is_ref,
         } = &param.kind
         else {
-            return None;
+            continue;
         };
 
-        let param_type = match get_type(type_annotation.clone(), None, &span, errors) {
-            Some(t) => t,
-            None => return None,
+        if scope.borrow().has_local_variable(identifier) {
+            let kind = ErrorKind::DuplicateParameter(identifier.clone());
+            errors.add(kind, span);
+            continue;
+        }
+
+        // unknown types still declare the parameter,
+        // so its uses in the body aren't reported
+        let param_type = match type_annotation {
+            Some(t) => match get_type(t, &scope.borrow(), errors) {
+                Some(t) => t,
+                None => TypeKind::Error,
+            },
+            None => infer_param_type(identifier.clone(), &scope, errors, span),
         };
 
-        // declare in scope
+        // parameters are always new variables in the
+        // function's frame, even if a variable with
+        // the same name exists outside of it
         let symbol = scope
             .borrow_mut()
-            .assign_variable(identifier.clone(), param_type.clone());
-
-        if symbol.is_none() {
-            let kind = ErrorKind::ParamMismatchedTypes(identifier.clone());
-            errors.add(kind, span);
-            return None;
-        }
+            .declare_variable(identifier.clone(), param_type.clone());
 
         let bound_param = BoundParameter {
-            symbol: symbol.unwrap(),
+            symbol: symbol,
             param_type: param_type.clone(),
+            is_ref: *is_ref,
         };
 
         parameters.push(bound_param);
     }
 
-    Some(parameters)
+    parameters
 }
 
 fn bind_binary_expression(
@@ -330,15 +1210,16 @@ fn bind_binary_expression(
     errors: &mut ErrorBag,
     span: Span,
 ) -> Option<BoundNode> {
-    let lhs = match bind(lhs, scope.clone(), errors) {
-        Some(n) => n,
-        None => return None,
-    };
+    let lhs = bind(lhs, scope.clone(), errors);
+    let rhs = bind(rhs, scope.clone(), errors);
 
-    let rhs = match bind(rhs, scope, errors) {
-        Some(n) => n,
-        None => return None,
-    };
+    if lhs.node_type.is_error() || rhs.node_type.is_error() {
+        return None;
+    }
+
+    if lhs.node_type.is_inferred() || rhs.node_type.is_inferred() {
+        return bind_inferred_binary_expression(lhs, op, rhs, scope, span);
+    }
 
     let op_type = match op.return_type_binary(&lhs, &rhs, errors) {
         Some(t) => t,
@@ -355,6 +1236,50 @@ fn bind_binary_expression(
     Some(node)
 }
 
+fn bind_inferred_binary_expression(
+    lhs: BoundNode,
+    op: &Operator,
+    rhs: BoundNode,
+    scope: Rc<RefCell<BoundScope>>,
+    span: Span,
+) -> Option<BoundNode> {
+    // an operand is an untyped parameter, the operator tells us
+    // what it's used as, any type errors are reported once
+    // the program is bound with the inferred types
+    let (used_as, op_type) = match op {
+        Operator::IntegerDivision | Operator::Modulo => (Some(TypeKind::Int), TypeKind::Int),
+        Operator::And | Operator::Or => (Some(TypeKind::Boolean), TypeKind::Boolean),
+        Operator::Equality
+        | Operator::Inequality
+        | Operator::LesserThan
+        | Operator::GreaterThan
+        | Operator::LesserThanOrEqual
+        | Operator::GreaterThanOrEqual => (None, TypeKind::Boolean),
+        // the result has the untyped operand's type, so
+        // it follows whatever the operand is inferred as
+        _ if lhs.node_type.is_inferred() => (None, lhs.node_type.clone()),
+        _ => (None, rhs.node_type.clone()),
+    };
+
+    match used_as {
+        Some(used_as) => {
+            infer_from_use(&lhs.node_type, &used_as, &scope);
+            infer_from_use(&rhs.node_type, &used_as, &scope);
+        }
+        // both operands have the same type
+        None => infer_from_use(&lhs.node_type, &rhs.node_type, &scope),
+    }
+
+    let kind = BoundNodeKind::BinaryExpression {
+        lhs: Box::new(lhs),
+        op: op.clone(),
+        rhs: Box::new(rhs),
+    };
+
+    let node = BoundNode::new(kind, op_type, span);
+    Some(node)
+}
+
 fn bind_unary_expression(
     op: &Operator,
     rhs: &SyntaxToken,
@@ -362,14 +1287,24 @@ fn bind_unary_expression(
     errors: &mut ErrorBag,
     span: Span,
 ) -> Option<BoundNode> {
-    let rhs = match bind(rhs, scope, errors) {
-        Some(n) => n,
-        None => return None,
-    };
+    let rhs = bind(rhs, scope.clone(), errors);
 
-    let op_type = match op.return_type_unary(&rhs, errors) {
-        Some(t) => t,
-        None => return None,
+    if rhs.node_type.is_error() {
+        return None;
+    }
+
+    // negation keeps the untyped operand's type,
+    // like arithmetic on untyped operands
+    let op_type = match (op, &rhs.node_type) {
+        (Operator::Not, TypeKind::Infer(_)) => {
+            infer_from_use(&rhs.node_type, &TypeKind::Boolean, &scope);
+            TypeKind::Boolean
+        }
+        (_, TypeKind::Infer(_)) => rhs.node_type.clone(),
+        _ => match op.return_type_unary(&rhs, errors) {
+            Some(t) => t,
+            None => return None,
+        },
     };
 
     let kind = BoundNodeKind::UnaryExpression {
@@ -387,6 +1322,12 @@ fn bind_integer_literal(value: i64, _errors: &mut ErrorBag, span: Span) -> Optio
     Some(node)
 }
 
+fn bind_real_literal(value: f64, _errors: &mut ErrorBag, span: Span) -> Option<BoundNode> {
+    let kind = BoundNodeKind::RealLiteral(value);
+    let node = BoundNode::new(kind, TypeKind::Real, span);
+    Some(node)
+}
+
 fn bind_boolean_literal(value: bool, _errors: &mut ErrorBag, span: Span) -> Option<BoundNode> {
     let kind = BoundNodeKind::BooleanLiteral(value);
     let node = BoundNode::new(kind, TypeKind::Boolean, span);
@@ -399,6 +1340,46 @@ fn bind_string_literal(value: String, _errors: &mut ErrorBag, span: Span) -> Opt
     Some(node)
 }
 
+fn bind_interpolated_string(
+    parts: &Vec<SyntaxToken>,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<BoundNode> {
+    let mut bound_parts: Vec<BoundNode> = Vec::new();
+    let mut failed = false;
+
+    for part in parts.iter() {
+        let bound_part = bind(part, scope.clone(), errors);
+
+        // only primitives can be formatted
+        match bound_part.node_type {
+            TypeKind::Error => {
+                failed = true;
+                continue;
+            }
+            ref t if t.is_primitive() || t.is_inferred() => {}
+            _ => {
+                let kind = ErrorKind::CannotInterpolateType(bound_part.node_type.clone());
+                errors.add(kind, part.span);
+                failed = true;
+                continue;
+            }
+        }
+
+        bound_parts.push(bound_part);
+    }
+
+    if failed {
+        return None;
+    }
+
+    let kind = BoundNodeKind::InterpolatedString { parts: bound_parts };
+
+    let node = BoundNode::new(kind, TypeKind::String, span);
+    Some(node)
+}
+
 fn bind_assignment_expression(
     identifier: String,
     value: &SyntaxToken,
@@ -406,23 +1387,25 @@ fn bind_assignment_expression(
     errors: &mut ErrorBag,
     span: Span,
 ) -> Option<BoundNode> {
-    let value = match bind(value, scope.clone(), errors) {
-        Some(v) => v,
-        None => return None,
-    };
+    let value = bind(value, scope.clone(), errors);
 
+    // a failed value still declares the variable,
+    // its type is unknown so uses aren't reported
     let node_type = value.node_type.clone();
     let symbol = scope
         .borrow_mut()
         .assign_variable(identifier.clone(), node_type.clone());
 
     let kind = match symbol {
-        Some(s) => BoundNodeKind::AssignmentExpression {
-            symbol: s,
-            value: Box::new(value),
-        },
-        None => {
-            errors.add(ErrorKind::AssignMismatchedTypes, span);
+        Ok(s) => {
+            infer_from_use(&node_type, &s.var_type, &scope);
+            BoundNodeKind::AssignmentExpression {
+                symbol: s,
+                value: Box::new(value),
+            }
+        }
+        Err(error_kind) => {
+            errors.add(error_kind, span);
             return None;
         }
     };
@@ -431,14 +1414,241 @@ fn bind_assignment_expression(
     Some(node)
 }
 
-fn bind_call_expression(
+fn bind_variable_declaration(
+    identifier: String,
+    type_annotation: &TypeSyntax,
+    value: &Option<Box<SyntaxToken>>,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<BoundNode> {
+    let var_type = match get_type(type_annotation, &scope.borrow(), errors) {
+        Some(t) => t,
+        None => TypeKind::Error,
+    };
+
+    // the value is bound first so it can't refer
+    // to the variable it's declaring
+    let value = match value {
+        Some(v) => Some(bind_declared_value(v, &var_type, scope.clone(), errors)),
+        None => None,
+    };
+
+    if scope.borrow().has_local_variable(&identifier) {
+        errors.add(ErrorKind::VariableAlreadyDeclared(identifier), span);
+        return None;
+    }
+
+    let symbol = scope
+        .borrow_mut()
+        .declare_variable(identifier, var_type.clone());
+
+    if var_type.is_error() {
+        return None;
+    }
+
+    let (kind, node_type) = match value {
+        Some(value) => {
+            if value.node_type.is_error() {
+                return None;
+            }
+
+            infer_from_use(&value.node_type, &var_type, &scope);
+            if !var_type.is_assignable_from(&value.node_type) {
+                let error_kind = ErrorKind::AssignMismatchedTypes {
+                    expected: var_type,
+                    found: value.node_type,
+                };
+                errors.add(error_kind, span);
+                return None;
+            }
+
+            let kind = BoundNodeKind::AssignmentExpression {
+                symbol: symbol,
+                value: Box::new(value),
+            };
+            (kind, var_type)
+        }
+        None => {
+            let kind = BoundNodeKind::VariableDeclaration { symbol: symbol };
+            (kind, TypeKind::Void)
+        }
+    };
+
+    let node = BoundNode::new(kind, node_type, span);
+    Some(node)
+}
+
+fn bind_declared_value(
+    value: &SyntaxToken,
+    var_type: &TypeKind,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+) -> BoundNode {
+    // an empty array has no elements to infer
+    // its type from, it takes the declared type
+    match (&value.kind, var_type) {
+        (SyntaxKind::ArrayLiteralExpression { elements }, TypeKind::Array(_))
+            if elements.is_empty() =>
+        {
+            let kind = BoundNodeKind::ArrayLiteral {
+                elements: Vec::new(),
+            };
+
+            BoundNode::new(kind, var_type.clone(), value.span)
+        }
+        _ => bind(value, scope, errors),
+    }
+}
+
+fn fold_constant(node: &BoundNode) -> Option<ConstantValue> {
+    // literals and operators on them are folded, uses of
+    // folded constants were already bound as literals
+    match &node.kind {
+        BoundNodeKind::NumberLiteral(v) => Some(ConstantValue::Int(*v)),
+        BoundNodeKind::RealLiteral(v) => Some(ConstantValue::Real(*v)),
+        BoundNodeKind::BooleanLiteral(v) => Some(ConstantValue::Boolean(*v)),
+        BoundNodeKind::StringLiteral(v) => Some(ConstantValue::String(v.clone())),
+        BoundNodeKind::UnaryExpression { op, rhs } => fold_unary(op, fold_constant(rhs)?),
+        BoundNodeKind::BinaryExpression { lhs, op, rhs } => {
+            fold_binary(fold_constant(lhs)?, op, fold_constant(rhs)?)
+        }
+        _ => None,
+    }
+}
+
+fn fold_unary(op: &Operator, rhs: ConstantValue) -> Option<ConstantValue> {
+    match (op, rhs) {
+        (Operator::Subtraction, ConstantValue::Int(v)) => v.checked_neg().map(ConstantValue::Int),
+        (Operator::Subtraction, ConstantValue::Real(v)) => Some(ConstantValue::Real(-v)),
+        (Operator::Not, ConstantValue::Boolean(v)) => Some(ConstantValue::Boolean(!v)),
+        _ => None,
+    }
+}
+
+fn fold_binary(lhs: ConstantValue, op: &Operator, rhs: ConstantValue) -> Option<ConstantValue> {
+    // computed like the evaluator does, values that would
+    // fail at runtime, like a division by zero, aren't folded
+    let value = match (lhs, rhs) {
+        (ConstantValue::Int(lhs), ConstantValue::Int(rhs)) => match op {
+            Operator::Addition => ConstantValue::Int(lhs.checked_add(rhs)?),
+            Operator::Subtraction => ConstantValue::Int(lhs.checked_sub(rhs)?),
+            Operator::Multiplication => ConstantValue::Int(lhs.checked_mul(rhs)?),
+            Operator::Division | Operator::IntegerDivision => {
+                ConstantValue::Int(lhs.checked_div(rhs)?)
+            }
+            Operator::Modulo => ConstantValue::Int(lhs.checked_rem(rhs)?),
+            op => fold_comparison(op, lhs.partial_cmp(&rhs))?,
+        },
+        (ConstantValue::Boolean(lhs), ConstantValue::Boolean(rhs)) => match op {
+            Operator::And => ConstantValue::Boolean(lhs && rhs),
+            Operator::Or => ConstantValue::Boolean(lhs || rhs),
+            op => fold_comparison(op, lhs.partial_cmp(&rhs))?,
+        },
+        (ConstantValue::String(lhs), ConstantValue::String(rhs)) => match op {
+            Operator::Addition => ConstantValue::String(format!("{}{}", lhs, rhs)),
+            op => fold_comparison(op, lhs.partial_cmp(&rhs))?,
+        },
+        (ConstantValue::String(lhs), rhs) => match op {
+            Operator::Addition => ConstantValue::String(format!("{}{}", lhs, rhs.to_string())),
+            _ => return None,
+        },
+        (lhs, ConstantValue::String(rhs)) => match op {
+            Operator::Addition => ConstantValue::String(format!("{}{}", lhs.to_string(), rhs)),
+            _ => return None,
+        },
+        // ints are widened when used with reals
+        (lhs, rhs) => {
+            let lhs = lhs.as_real()?;
+            let rhs = rhs.as_real()?;
+            match op {
+                Operator::Addition => ConstantValue::Real(lhs + rhs),
+                Operator::Subtraction => ConstantValue::Real(lhs - rhs),
+                Operator::Multiplication => ConstantValue::Real(lhs * rhs),
+                Operator::Division => ConstantValue::Real(lhs / rhs),
+                op => fold_comparison(op, lhs.partial_cmp(&rhs))?,
+            }
+        }
+    };
+
+    Some(value)
+}
+
+fn fold_comparison(op: &Operator, ordering: Option<Ordering>) -> Option<ConstantValue> {
+    // nan isn't ordered, it's left to the evaluator
+    let ordering = ordering?;
+    let value = match op {
+        Operator::Equality => ordering == Ordering::Equal,
+        Operator::Inequality => ordering != Ordering::Equal,
+        Operator::LesserThan => ordering == Ordering::Less,
+        Operator::GreaterThan => ordering == Ordering::Greater,
+        Operator::LesserThanOrEqual => ordering != Ordering::Greater,
+        Operator::GreaterThanOrEqual => ordering != Ordering::Less,
+        _ => return None,
+    };
+
+    Some(ConstantValue::Boolean(value))
+}
+
+fn bind_constant_declaration(
+    identifier: String,
+    value: &SyntaxToken,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<BoundNode> {
+    let value = bind(value, scope.clone(), errors);
+
+    if scope.borrow().has_local_variable(&identifier) {
+        errors.add(ErrorKind::VariableAlreadyDeclared(identifier), span);
+        return None;
+    }
+
+    // a failed value still declares the constant,
+    // its type is unknown so uses aren't reported
+    let node_type = value.node_type.clone();
+    let constant_value = fold_constant(&value);
+    let symbol = scope
+        .borrow_mut()
+        .declare_constant(identifier, node_type.clone(), constant_value);
+
+    let kind = BoundNodeKind::AssignmentExpression {
+        symbol: symbol,
+        value: Box::new(value),
+    };
+
+    let node = BoundNode::new(kind, node_type, span);
+    Some(node)
+}
+
+fn bind_call_expression(
+    identifier: String,
+    args: &Vec<SyntaxToken>,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<BoundNode> {
+    let symbol = scope.borrow().get_function(identifier.clone());
+    bind_call(identifier, symbol, args, scope, errors, span)
+}
+
+fn bind_call(
     identifier: String,
+    symbol: Option<FunctionSymbol>,
     args: &Vec<SyntaxToken>,
     scope: Rc<RefCell<BoundScope>>,
     errors: &mut ErrorBag,
     span: Span,
 ) -> Option<BoundNode> {
-    let symbol = match scope.borrow().get_function(identifier.clone()) {
+    // arguments are bound first, so they're
+    // reported even if the call is invalid
+    let mut bound_args: Vec<BoundNode> = Vec::new();
+    for arg in args.iter() {
+        let bound_arg = bind(arg, scope.clone(), errors);
+        bound_args.push(bound_arg);
+    }
+
+    let symbol = match symbol {
         Some(sym) => sym,
         None => {
             let kind = ErrorKind::CannotFindFunction(identifier);
@@ -462,28 +1672,34 @@ fn bind_call_expression(
         return None;
     }
 
-    let mut bound_args: Vec<BoundNode> = Vec::new();
-    for index in 0..num_params {
-        let param = &params[index];
-        let arg = &args[index];
+    let mut failed = false;
+    for (param, bound_arg) in params.iter().zip(bound_args.iter()) {
+        if bound_arg.node_type.is_error() {
+            failed = true;
+            continue;
+        }
 
-        let bound_arg = match bind(arg, scope.clone(), errors) {
-            Some(a) => a,
-            None => return None,
-        };
+        infer_from_use(&bound_arg.node_type, &param.param_type, &scope);
+
+        if param.is_ref && !check_ref_arg(&identifier, param, bound_arg, errors) {
+            failed = true;
+            continue;
+        }
 
-        if param.param_type != bound_arg.node_type {
+        if !param.param_type.is_assignable_from(&bound_arg.node_type) {
             let kind = ErrorKind::MismatchedArgTypes {
                 id: identifier.clone(),
                 expected: param.param_type.clone(),
-                found: bound_arg.node_type,
+                found: bound_arg.node_type.clone(),
             };
 
             errors.add(kind, span);
-            return None;
+            failed = true;
         }
+    }
 
-        bound_args.push(bound_arg);
+    if failed {
+        return None;
     }
 
     let ret_type = symbol.ret_type.clone();
@@ -496,6 +1712,53 @@ fn bind_call_expression(
     Some(node)
 }
 
+fn check_ref_arg(
+    identifier: &String,
+    param: &BoundParameter,
+    arg: &BoundNode,
+    errors: &mut ErrorBag,
+) -> bool {
+    // the parameter's value is written back to the argument,
+    // so it has to be something that can be assigned to
+    match &arg.kind {
+        BoundNodeKind::ReferenceExpression(symbol) if symbol.is_constant => {
+            let kind = ErrorKind::CannotAssignToConstant(symbol.identifier.clone());
+            errors.add(kind, arg.span);
+            return false;
+        }
+        BoundNodeKind::ReferenceExpression(_)
+        | BoundNodeKind::FieldExpression { .. }
+        | BoundNodeKind::IndexExpression { .. } => {}
+        _ => {
+            let kind = ErrorKind::RefArgNotAssignable {
+                id: identifier.clone(),
+                param: param.symbol.identifier.clone(),
+            };
+
+            errors.add(kind, arg.span);
+            return false;
+        }
+    }
+
+    // the value can't be widened when it's written
+    // back, so the types have to match exactly
+    if param.param_type.is_inferred()
+        || arg.node_type.is_inferred()
+        || param.param_type == arg.node_type
+    {
+        return true;
+    }
+
+    let kind = ErrorKind::MismatchedArgTypes {
+        id: identifier.clone(),
+        expected: param.param_type.clone(),
+        found: arg.node_type.clone(),
+    };
+
+    errors.add(kind, arg.span);
+    false
+}
+
 fn bind_reference_expression(
     identifier: String,
     scope: Rc<RefCell<BoundScope>>,
@@ -510,12 +1773,57 @@ fn bind_reference_expression(
         }
     };
 
+    // uses of a variable with an unknown type are poisoned
+    if symbol.var_type.is_error() {
+        return None;
+    }
+
+    // folded constants are replaced with their value
+    match &symbol.constant_value {
+        Some(ConstantValue::Int(v)) => return bind_integer_literal(*v, errors, span),
+        Some(ConstantValue::Real(v)) => return bind_real_literal(*v, errors, span),
+        Some(ConstantValue::Boolean(v)) => return bind_boolean_literal(*v, errors, span),
+        Some(ConstantValue::String(v)) => return bind_string_literal(v.clone(), errors, span),
+        None => {}
+    }
+
     let var_type = symbol.var_type.clone();
     let kind = BoundNodeKind::ReferenceExpression(symbol);
     let node = BoundNode::new(kind, var_type, span);
     Some(node)
 }
 
+fn declare_method(
+    method: TypeMethodRepresentation,
+    scope: Rc<RefCell<BoundScope>>,
+) -> FunctionSymbol {
+    // parameters are declared in the caller's scope, since
+    // the arguments are assigned in the caller's frame
+    let mut param_scope = BoundScope::new(scope);
+    let mut params = Vec::<BoundParameter>::new();
+
+    for param in method.params {
+        let param_type = param.param_type.clone();
+        let symbol = param_scope.declare_variable(param.identifier, param.param_type);
+
+        let bound_parameter = BoundParameter {
+            symbol: symbol,
+            param_type: param_type,
+            is_ref: false,
+        };
+
+        params.push(bound_parameter);
+    }
+
+    // methods live outside of the user's scopes, so they
+    // can't clash with functions of the same name
+    let mut method_scope = BoundScope::new_root(param_scope.inference());
+    match method_scope.declare_function(method.identifier, params, method.ret_type) {
+        Some(symbol) => symbol,
+        None => unreachable!(),
+    }
+}
+
 fn bind_object_member_expression(
     base: &SyntaxToken,
     next: &SyntaxToken,
@@ -523,47 +1831,483 @@ fn bind_object_member_expression(
     errors: &mut ErrorBag,
     span: Span,
 ) -> Option<BoundNode> {
-    let base_node = match bind(base, scope.clone(), errors) {
-        Some(n) => n,
-        None => return None,
+    let base_node = bind(base, scope.clone(), errors);
+    bind_member(base_node, next, scope, errors, span)
+}
+
+fn bind_member(
+    base_node: BoundNode,
+    next: &SyntaxToken,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<BoundNode> {
+    // members of an unknown type can't be looked up
+    if base_node.node_type.is_error() {
+        return None;
+    }
+
+    if let TypeKind::Class(class_type) = &base_node.node_type {
+        let class = scope.borrow().get_class_symbol(class_type);
+        return bind_class_member(base_node, &class, next, scope, errors, span);
+    }
+
+    let next = match &next.kind {
+        SyntaxKind::CallExpression { identifier, args } => {
+            let method = base_node
+                .node_type
+                .reflection_methods()
+                .into_iter()
+                .find(|m| &m.identifier == identifier);
+
+            let symbol = match method {
+                Some(m) => Some(declare_method(m, scope.clone())),
+                None => None,
+            };
+
+            match bind_call(identifier.clone(), symbol, args, scope, errors, next.span) {
+                Some(n) => n,
+                None => BoundNode::error(next.span),
+            }
+        }
+        // objects don't have any fields
+        _ => {
+            let inference = scope.borrow().inference();
+            let root = BoundScope::new_root(inference);
+            bind(next, Rc::new(RefCell::new(root)), errors)
+        }
     };
 
-    // create a scope with all object member methods
-    // and the run regular binding with that scope
-    let mut object_scope = BoundScope::new_root();
-    let type_methods = base_node.node_type.reflection_methods();
+    let node_type = next.node_type.clone();
+    let kind = BoundNodeKind::ObjectMemberExpression {
+        base: Box::new(base_node),
+        next: Box::new(next),
+    };
+
+    let node = BoundNode::new(kind, node_type, span);
+    Some(node)
+}
+
+fn get_class_member<T>(
+    member: Option<T>,
+    class: &ClassSymbol,
+    identifier: &str,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<T> {
+    if member.is_none() {
+        let kind = ErrorKind::UnknownClassMember {
+            class: class.class_type.identifier.clone(),
+            member: identifier.to_string(),
+        };
+
+        errors.add(kind, span);
+    }
+
+    member
+}
+
+fn check_member_access(
+    is_private: bool,
+    owner_id: u64,
+    class: &ClassSymbol,
+    identifier: &str,
+    scope: &Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> bool {
+    // private members are only accessible inside
+    // the class that declared them
+    if is_private && scope.borrow().class_id() != Some(owner_id) {
+        // an inherited member is reported
+        // on the ancestor that declared it
+        let mut owner = class.clone();
+        while owner.class_type.class_id != owner_id {
+            let Some(parent) = &owner.parent else {
+                break;
+            };
+
+            owner = scope.borrow().get_class_symbol(parent);
+        }
+
+        let kind = ErrorKind::PrivateClassMember {
+            class: owner.class_type.identifier,
+            member: identifier.to_string(),
+        };
+
+        errors.add(kind, span);
+        return false;
+    }
+
+    true
+}
+
+fn bind_class_member(
+    object: BoundNode,
+    class: &ClassSymbol,
+    member: &SyntaxToken,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<BoundNode> {
+    // the member's arguments and values are bound in the
+    // caller's scope, only the names are looked up in the class
+    match &member.kind {
+        SyntaxKind::ReferenceExpression(identifier) => {
+            let field = class.get_field(identifier).cloned();
+            let field = match get_class_member(field, class, identifier, errors, member.span) {
+                Some(f) => f,
+                None => return None,
+            };
+
+            if !check_member_access(
+                field.is_private,
+                field.owner_id,
+                class,
+                identifier,
+                &scope,
+                errors,
+                member.span,
+            ) {
+                return None;
+            }
+
+            // uses of a field with an unknown type are poisoned
+            if field.field_type.is_error() {
+                return None;
+            }
+
+            let node_type = field.field_type.clone();
+            let kind = BoundNodeKind::FieldExpression {
+                base: Box::new(object),
+                field: field,
+            };
+
+            let node = BoundNode::new(kind, node_type, span);
+            Some(node)
+        }
+        SyntaxKind::AssignmentExpression { identifier, value } => {
+            let value = bind(value, scope.clone(), errors);
+
+            let field = class.get_field(identifier).cloned();
+            let field = match get_class_member(field, class, identifier, errors, member.span) {
+                Some(f) => f,
+                None => return None,
+            };
+
+            if !check_member_access(
+                field.is_private,
+                field.owner_id,
+                class,
+                identifier,
+                &scope,
+                errors,
+                member.span,
+            ) {
+                return None;
+            }
+
+            if value.node_type.is_error() {
+                return None;
+            }
+
+            infer_from_use(&value.node_type, &field.field_type, &scope);
+            if !field.field_type.is_assignable_from(&value.node_type) {
+                let kind = ErrorKind::AssignMismatchedTypes {
+                    expected: field.field_type,
+                    found: value.node_type,
+                };
+
+                errors.add(kind, span);
+                return None;
+            }
+
+            let node_type = field.field_type.clone();
+            let kind = BoundNodeKind::FieldAssignmentExpression {
+                base: Box::new(object),
+                field: field,
+                value: Box::new(value),
+            };
+
+            let node = BoundNode::new(kind, node_type, span);
+            Some(node)
+        }
+        SyntaxKind::CallExpression { identifier, args } => {
+            let method = class.get_method(identifier).cloned();
+            let method = match get_class_member(method, class, identifier, errors, member.span) {
+                Some(m) => m,
+                None => return None,
+            };
+
+            if !check_member_access(
+                method.is_private,
+                method.owner_id,
+                class,
+                identifier,
+                &scope,
+                errors,
+                member.span,
+            ) {
+                return None;
+            }
+
+            let symbol = Some(method.function.clone());
+            let call = match bind_call(identifier.clone(), symbol, args, scope, errors, member.span)
+            {
+                Some(c) => c,
+                None => return None,
+            };
+
+            let BoundNodeKind::BoundCallExpression { args, .. } = call.kind else {
+                unreachable!()
+            };
+
+            let kind = BoundNodeKind::MethodCallExpression {
+                base: Box::new(object),
+                method: method,
+                args: args,
+            };
+
+            let node = BoundNode::new(kind, call.node_type, span);
+            Some(node)
+        }
+        SyntaxKind::IndexExpression { base, index } => {
+            let base = match bind_class_member(object, class, base, scope.clone(), errors, span) {
+                Some(b) => b,
+                None => return None,
+            };
 
-    for method in type_methods {
-        let mut params = Vec::<BoundParameter>::new();
-        let mut scope_mut = scope.borrow_mut();
+            bind_index_expression(base, index, scope, errors, span)
+        }
+        SyntaxKind::IndexAssignmentExpression { base, index, value } => {
+            let base = match bind_class_member(object, class, base, scope.clone(), errors, span) {
+                Some(b) => b,
+                None => return None,
+            };
 
-        for param in method.params {
-            let param = match scope_mut.assign_variable(param.identifier, param.param_type) {
-                Some(p) => p,
+            bind_index_assignment_expression(base, index, value, scope, errors, span)
+        }
+        SyntaxKind::ObjectMemberExpression { base, next } => {
+            // i.e. the `b.c` in a.b.c
+            let base = match bind_class_member(object, class, base, scope.clone(), errors, span) {
+                Some(b) => b,
                 None => return None,
             };
 
-            let param_type = param.var_type.clone();
-            let bound_parameter = BoundParameter {
-                symbol: param,
-                param_type: param_type,
+            bind_member(base, next, scope, errors, span)
+        }
+        _ => {
+            let kind = ErrorKind::ExpectedToken("object member".to_string());
+            errors.add(kind, member.span);
+            None
+        }
+    }
+}
+
+fn bind_array_literal(
+    elements: &Vec<SyntaxToken>,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<BoundNode> {
+    let mut bound_elements: Vec<BoundNode> = Vec::new();
+    for element in elements.iter() {
+        let bound_element = bind(element, scope.clone(), errors);
+        bound_elements.push(bound_element);
+    }
+
+    if bound_elements.iter().any(|e| e.node_type.is_error()) {
+        return None;
+    }
+
+    // the element type is inferred from the elements,
+    // mixing ints and reals widens the array to reals
+    let mut element_type = match bound_elements.first() {
+        Some(e) => e.node_type.clone(),
+        None => {
+            errors.add(ErrorKind::CannotInferArrayType, span);
+            return None;
+        }
+    };
+
+    for element in bound_elements.iter() {
+        if element.node_type.is_assignable_from(&element_type) {
+            element_type = element.node_type.clone();
+        } else if !element_type.is_assignable_from(&element.node_type) {
+            let kind = ErrorKind::ArrayElementMismatchedTypes {
+                expected: element_type,
+                found: element.node_type.clone(),
             };
 
-            params.push(bound_parameter);
+            errors.add(kind, element.span);
+            return None;
+        }
+    }
+
+    let node_type = TypeKind::Array(Box::new(element_type));
+    let kind = BoundNodeKind::ArrayLiteral {
+        elements: bound_elements,
+    };
+
+    let node = BoundNode::new(kind, node_type, span);
+    Some(node)
+}
+
+fn bind_index(
+    base: BoundNode,
+    index: &SyntaxToken,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+) -> Option<(BoundNode, BoundNode, TypeKind)> {
+    let index = bind(index, scope.clone(), errors);
+    infer_from_use(&index.node_type, &TypeKind::Int, &scope);
+
+    let element_type = match &base.node_type {
+        TypeKind::Array(element_type) => *element_type.clone(),
+        TypeKind::Infer(array) => {
+            let inference = scope.borrow().inference();
+            let mut inference = inference.borrow_mut();
+            inference.element_type(*array)
+        }
+        TypeKind::Error => return None,
+        _ => {
+            let kind = ErrorKind::CannotIndexType(base.node_type.clone());
+            errors.add(kind, base.span);
+            return None;
         }
+    };
 
-        object_scope.declare_function(method.identifier, params, method.ret_type);
+    match &index.node_type {
+        TypeKind::Int | TypeKind::Infer(_) => {}
+        TypeKind::Error => return None,
+        _ => {
+            let kind = ErrorKind::IndexMustBeInt(index.node_type.clone());
+            errors.add(kind, index.span);
+            return None;
+        }
     }
 
-    let next = match bind(next, Rc::new(RefCell::new(object_scope)), errors) {
-        Some(n) => n,
+    Some((base, index, element_type))
+}
+
+fn bind_index_expression(
+    base: BoundNode,
+    index: &SyntaxToken,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<BoundNode> {
+    let (base, index, element_type) = match bind_index(base, index, scope, errors) {
+        Some(i) => i,
         None => return None,
     };
 
-    let node_type = next.node_type.clone();
-    let kind = BoundNodeKind::ObjectMemberExpression {
-        base: Box::new(base_node),
-        next: Box::new(next),
+    let kind = BoundNodeKind::IndexExpression {
+        base: Box::new(base),
+        index: Box::new(index),
+    };
+
+    let node = BoundNode::new(kind, element_type, span);
+    Some(node)
+}
+
+fn bind_index_assignment_expression(
+    base: BoundNode,
+    index: &SyntaxToken,
+    value: &SyntaxToken,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<BoundNode> {
+    let indexed = bind_index(base, index, scope.clone(), errors);
+    let value = bind(value, scope.clone(), errors);
+
+    let (base, index, element_type) = match indexed {
+        Some(i) => i,
+        None => return None,
+    };
+
+    if value.node_type.is_error() {
+        return None;
+    }
+
+    infer_from_use(&value.node_type, &element_type, &scope);
+    if !element_type.is_assignable_from(&value.node_type) {
+        let error_kind = ErrorKind::AssignMismatchedTypes {
+            expected: element_type,
+            found: value.node_type,
+        };
+        errors.add(error_kind, span);
+        return None;
+    }
+
+    let kind = BoundNodeKind::IndexAssignmentExpression {
+        base: Box::new(base),
+        index: Box::new(index),
+        value: Box::new(value),
+    };
+
+    let node = BoundNode::new(kind, element_type, span);
+    Some(node)
+}
+
+fn bind_class_instantiation(
+    class: ClassSymbol,
+    args: &Vec<SyntaxToken>,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+    span: Span,
+) -> Option<BoundNode> {
+    let ctor = format!("{}.constructor()", class.class_type.identifier);
+    let node_type = TypeKind::Class(class.class_type.clone());
+
+    let args = match &class.constructor {
+        Some(constructor) => {
+            if !check_member_access(
+                constructor.is_private,
+                constructor.owner_id,
+                &class,
+                "constructor",
+                &scope,
+                errors,
+                span,
+            ) {
+                return None;
+            }
+
+            let symbol = Some(constructor.function.clone());
+            let call = match bind_call(ctor, symbol, args, scope, errors, span) {
+                Some(c) => c,
+                None => return None,
+            };
+
+            let BoundNodeKind::BoundCallExpression { args, .. } = call.kind else {
+                unreachable!()
+            };
+
+            args
+        }
+        None => {
+            // classes without a constructor
+            // don't take any arguments
+            if args.len() != 0 {
+                let kind = ErrorKind::MismatchedNumberOfArgs {
+                    id: ctor,
+                    expected: 0,
+                    found: args.len(),
+                };
+
+                errors.add(kind, span);
+                return None;
+            }
+
+            Box::new(Vec::new())
+        }
+    };
+
+    let kind = BoundNodeKind::ClassInstantiation {
+        constructor: class.constructor,
+        args: args,
     };
 
     let node = BoundNode::new(kind, node_type, span);
@@ -571,21 +2315,25 @@ fn bind_object_member_expression(
 }
 
 fn bind_instantiation_expression(
-    type_name: String,
-    type_param: Option<String>,
+    type_syntax: &TypeSyntax,
     args: &Vec<SyntaxToken>,
-    _scope: Rc<RefCell<BoundScope>>,
+    scope: Rc<RefCell<BoundScope>>,
     errors: &mut ErrorBag,
     span: Span,
 ) -> Option<BoundNode> {
-    let instantiation_type = match get_type(type_name.clone(), type_param, &span, errors) {
+    let instantiation_type = match get_type(type_syntax, &scope.borrow(), errors) {
         Some(t) => t,
         None => return None,
     };
 
+    if let TypeKind::Class(class_type) = &instantiation_type {
+        let class = scope.borrow().get_class_symbol(class_type);
+        return bind_class_instantiation(class, args, scope, errors, span);
+    }
+
     if args.len() != 0 {
         // we don't support constructors with arguments yet
-        let ctor = format!("{}.constructor()", type_name);
+        let ctor = format!("{}.constructor()", type_syntax.identifier);
         let kind = ErrorKind::MismatchedNumberOfArgs {
             id: ctor,
             expected: 0,
@@ -601,7 +2349,7 @@ fn bind_instantiation_expression(
     Some(node)
 }
 
-pub fn bind(
+fn bind_token(
     token: &SyntaxToken,
     scope: Rc<RefCell<BoundScope>>,
     errors: &mut ErrorBag,
@@ -611,9 +2359,16 @@ pub fn bind(
         SyntaxKind::Scope { subtokens } => bind_block(&subtokens, scope, true, errors, span),
         SyntaxKind::OutputStatement { expr } => bind_output_statement(&expr, scope, errors, span),
         SyntaxKind::ReturnStatement { expr } => bind_return_statement(&expr, scope, errors, span),
-        SyntaxKind::IfStatement { condition, body } => {
-            bind_if_statement(&condition, &body, None, scope, errors, span)
-        }
+        SyntaxKind::IfStatement {
+            condition,
+            body,
+            else_body,
+        } => bind_if_statement(&condition, &body, else_body.as_deref(), scope, errors, span),
+        SyntaxKind::CaseStatement {
+            expr,
+            arms,
+            otherwise,
+        } => bind_case_statement(&expr, &arms, otherwise.as_deref(), scope, errors, span),
         SyntaxKind::FunctionDeclaration {
             identifier,
             parameters,
@@ -628,15 +2383,22 @@ pub fn bind(
             errors,
             span,
         ),
+        SyntaxKind::ClassDeclaration {
+            identifier,
+            parent,
+            members,
+        } => bind_class_declaration(identifier.clone(), parent, &members, scope, errors, span),
         SyntaxKind::ForLoop {
             identifier,
             lower_bound,
             upper_bound,
+            step,
             body,
         } => bind_for_statement(
             identifier.clone(),
-            lower_bound.clone(),
-            upper_bound.clone(),
+            &lower_bound,
+            &upper_bound,
+            step.as_deref(),
             &body,
             scope,
             errors,
@@ -645,6 +2407,8 @@ pub fn bind(
         SyntaxKind::WhileLoop { expr, body } => {
             bind_while_statement(&expr, &body, scope, errors, span)
         }
+        SyntaxKind::BreakStatement => bind_break_statement(scope, errors, span),
+        SyntaxKind::ContinueStatement => bind_continue_statement(scope, errors, span),
         SyntaxKind::BinaryExpression { lhs, op, rhs } => {
             bind_binary_expression(&lhs, &op, &rhs, scope, errors, span)
         }
@@ -654,15 +2418,34 @@ pub fn bind(
         SyntaxKind::IntegerLiteralExpression(value) => {
             bind_integer_literal(value.clone(), errors, span)
         }
+        SyntaxKind::RealLiteralExpression(value) => bind_real_literal(value.clone(), errors, span),
         SyntaxKind::BooleanLiteralExpression(value) => {
             bind_boolean_literal(value.clone(), errors, span)
         }
         SyntaxKind::StringLiteralExpression(value) => {
             bind_string_literal(value.clone(), errors, span)
         }
+        SyntaxKind::InterpolatedStringExpression { parts } => {
+            bind_interpolated_string(&parts, scope, errors, span)
+        }
         SyntaxKind::AssignmentExpression { identifier, value } => {
             bind_assignment_expression(identifier.clone(), value, scope, errors, span)
         }
+        SyntaxKind::VariableDeclaration {
+            identifier,
+            type_annotation,
+            value,
+        } => bind_variable_declaration(
+            identifier.clone(),
+            type_annotation,
+            value,
+            scope,
+            errors,
+            span,
+        ),
+        SyntaxKind::ConstantDeclaration { identifier, value } => {
+            bind_constant_declaration(identifier.clone(), value, scope, errors, span)
+        }
         SyntaxKind::CallExpression { identifier, args } => {
             bind_call_expression(identifier.clone(), &args, scope, errors, span)
         }
@@ -672,22 +2455,37 @@ pub fn bind(
         SyntaxKind::ObjectMemberExpression { base, next } => {
             bind_object_member_expression(&base, &next, scope, errors, span)
         }
-        SyntaxKind::InstantiationExpression {
-            type_name,
-            type_param,
-            args,
-        } => bind_instantiation_expression(
-            type_name.clone(),
-            type_param.clone(),
-            &args,
-            scope,
-            errors,
-            span,
-        ),
-        SyntaxKind::ParenthesizedExpression { inner } => bind(&inner, scope, errors),
+        SyntaxKind::InstantiationExpression { type_syntax, args } => {
+            bind_instantiation_expression(type_syntax, &args, scope, errors, span)
+        }
+        SyntaxKind::ArrayLiteralExpression { elements } => {
+            bind_array_literal(&elements, scope, errors, span)
+        }
+        SyntaxKind::IndexExpression { base, index } => {
+            let base = bind(&base, scope.clone(), errors);
+            bind_index_expression(base, &index, scope, errors, span)
+        }
+        SyntaxKind::IndexAssignmentExpression { base, index, value } => {
+            let base = bind(&base, scope.clone(), errors);
+            bind_index_assignment_expression(base, &index, &value, scope, errors, span)
+        }
+        SyntaxKind::ParenthesizedExpression { inner } => Some(bind(&inner, scope, errors)),
+        // already reported by the parser
+        SyntaxKind::Error => None,
         _ => {
             println!("unknown: {:?}", token.kind);
             unreachable!()
         }
     }
 }
+
+pub fn bind(
+    token: &SyntaxToken,
+    scope: Rc<RefCell<BoundScope>>,
+    errors: &mut ErrorBag,
+) -> BoundNode {
+    match bind_token(token, scope, errors) {
+        Some(node) => node,
+        None => BoundNode::error(token.span),
+    }
+}
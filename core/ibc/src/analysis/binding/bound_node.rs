@@ -3,7 +3,7 @@ use std::sync::Arc;
 use crate::analysis::{operator::Operator, span::Span};
 
 use super::{
-    symbols::{FunctionSymbol, VariableSymbol},
+    symbols::{ClassSymbol, FieldSymbol, FunctionSymbol, MethodSymbol, VariableSymbol},
     types::TypeKind,
 };
 
@@ -23,8 +23,132 @@ impl BoundNode {
         }
     }
 
+    pub fn error(span: Span) -> BoundNode {
+        BoundNode::new(BoundNodeKind::Error, TypeKind::Error, span)
+    }
+
+    pub fn contains_errors(&self) -> bool {
+        match &self.kind {
+            BoundNodeKind::Error => true,
+            BoundNodeKind::Module { imports, block } => {
+                imports.iter().any(|i| i.contains_errors()) || block.contains_errors()
+            }
+            BoundNodeKind::Block { children } => children.iter().any(|c| c.contains_errors()),
+            BoundNodeKind::OutputStatement { expr } => expr.contains_errors(),
+            BoundNodeKind::ReturnStatement { expr } => match expr {
+                Some(expr) => expr.contains_errors(),
+                None => false,
+            },
+            BoundNodeKind::IfStatement {
+                condition,
+                block,
+                else_block,
+            } => {
+                let else_errors = match else_block {
+                    Some(else_block) => else_block.contains_errors(),
+                    None => false,
+                };
+
+                condition.contains_errors() || block.contains_errors() || else_errors
+            }
+            BoundNodeKind::CaseStatement {
+                expr,
+                arms,
+                otherwise,
+            } => {
+                let otherwise_errors = match otherwise {
+                    Some(otherwise) => otherwise.contains_errors(),
+                    None => false,
+                };
+
+                expr.contains_errors()
+                    || arms
+                        .iter()
+                        .any(|a| a.value.contains_errors() || a.block.contains_errors())
+                    || otherwise_errors
+            }
+            BoundNodeKind::FunctionDeclaration { symbol: _, block } => block.contains_errors(),
+            BoundNodeKind::ClassDeclaration {
+                symbol: _,
+                initializers,
+                methods,
+            } => {
+                initializers.iter().any(|i| i.value.contains_errors())
+                    || methods.iter().any(|m| m.contains_errors())
+            }
+            BoundNodeKind::ForLoop {
+                iterator: _,
+                lower_bound,
+                upper_bound,
+                step,
+                block,
+            } => {
+                let step_errors = match step {
+                    Some(step) => step.contains_errors(),
+                    None => false,
+                };
+
+                lower_bound.contains_errors()
+                    || upper_bound.contains_errors()
+                    || step_errors
+                    || block.contains_errors()
+            }
+            BoundNodeKind::WhileLoop { expr, block } => {
+                expr.contains_errors() || block.contains_errors()
+            }
+            BoundNodeKind::BinaryExpression { lhs, op: _, rhs } => {
+                lhs.contains_errors() || rhs.contains_errors()
+            }
+            BoundNodeKind::UnaryExpression { op: _, rhs } => rhs.contains_errors(),
+            BoundNodeKind::AssignmentExpression { symbol: _, value } => value.contains_errors(),
+            BoundNodeKind::BoundCallExpression { symbol: _, args } => {
+                args.iter().any(|a| a.contains_errors())
+            }
+            BoundNodeKind::ObjectMemberExpression { base, next } => {
+                base.contains_errors() || next.contains_errors()
+            }
+            BoundNodeKind::ClassInstantiation {
+                constructor: _,
+                args,
+            } => args.iter().any(|a| a.contains_errors()),
+            BoundNodeKind::FieldExpression { base, field: _ } => base.contains_errors(),
+            BoundNodeKind::FieldAssignmentExpression {
+                base,
+                field: _,
+                value,
+            } => base.contains_errors() || value.contains_errors(),
+            BoundNodeKind::MethodCallExpression {
+                base,
+                method: _,
+                args,
+            } => base.contains_errors() || args.iter().any(|a| a.contains_errors()),
+            BoundNodeKind::ArrayLiteral { elements } => {
+                elements.iter().any(|e| e.contains_errors())
+            }
+            BoundNodeKind::IndexExpression { base, index } => {
+                base.contains_errors() || index.contains_errors()
+            }
+            BoundNodeKind::IndexAssignmentExpression { base, index, value } => {
+                base.contains_errors() || index.contains_errors() || value.contains_errors()
+            }
+            BoundNodeKind::InterpolatedString { parts } => {
+                parts.iter().any(|p| p.contains_errors())
+            }
+            BoundNodeKind::BreakStatement
+            | BoundNodeKind::ContinueStatement
+            | BoundNodeKind::ObjectExpression
+            | BoundNodeKind::ReferenceExpression(_)
+            | BoundNodeKind::VariableDeclaration { .. }
+            | BoundNodeKind::NumberLiteral(_)
+            | BoundNodeKind::RealLiteral(_)
+            | BoundNodeKind::BooleanLiteral(_)
+            | BoundNodeKind::StringLiteral(_) => false,
+        }
+    }
+
     pub fn to_string(&self) -> String {
         match &self.kind {
+            BoundNodeKind::Error => "<error>".to_string(),
             BoundNodeKind::Module { .. } => "Module".to_string(),
             BoundNodeKind::Block { .. } => "Block".to_string(),
             BoundNodeKind::OutputStatement { expr } => format!("output {}", &expr.to_string()),
@@ -41,29 +165,38 @@ impl BoundNode {
                 block: _,
                 else_block: _,
             } => format!("if {}", &condition.to_string()),
+            BoundNodeKind::CaseStatement { expr, .. } => format!("case {}", &expr.to_string()),
             BoundNodeKind::ForLoop {
                 iterator,
                 lower_bound,
                 upper_bound,
+                step,
                 block,
             } => {
+                let step_fmt = match step {
+                    Some(step) => format!(" step {}", step.to_string()),
+                    None => "".to_string(),
+                };
+
                 format!(
-                    "loop {} from {} to {} {} end",
+                    "loop {} from {} to {}{} {} end",
                     iterator.identifier,
-                    lower_bound,
-                    upper_bound,
+                    lower_bound.to_string(),
+                    upper_bound.to_string(),
+                    step_fmt,
                     block.to_string()
                 )
             }
             BoundNodeKind::WhileLoop { expr, block } => {
                 format!("loop while {} {} end", expr.to_string(), block.to_string())
             }
+            BoundNodeKind::BreakStatement => "break".to_string(),
+            BoundNodeKind::ContinueStatement => "continue".to_string(),
             BoundNodeKind::FunctionDeclaration { symbol, block: _ } => {
-                format!(
-                    "function {}(...) -> {}",
-                    symbol.identifier,
-                    symbol.ret_type.to_string()
-                )
+                format!("function {}", symbol.signature())
+            }
+            BoundNodeKind::ClassDeclaration { symbol, .. } => {
+                format!("class {}", symbol.class_type.identifier)
             }
             BoundNodeKind::BinaryExpression { lhs, op, rhs } => {
                 format!("{} {} {}", lhs.to_string(), op.to_string(), rhs.to_string())
@@ -74,6 +207,9 @@ impl BoundNode {
             BoundNodeKind::AssignmentExpression { symbol, value } => {
                 format!("{} = {}", symbol.identifier, value.to_string())
             }
+            BoundNodeKind::VariableDeclaration { symbol } => {
+                format!("{}: {}", symbol.identifier, symbol.var_type.to_string())
+            }
             BoundNodeKind::BoundCallExpression { symbol, args: _ } => {
                 format!("{}(...)", symbol.identifier)
             }
@@ -81,19 +217,69 @@ impl BoundNode {
             BoundNodeKind::ObjectMemberExpression { base, next } => {
                 format!("{}.{}", base.to_string(), next.to_string())
             }
+            BoundNodeKind::ClassInstantiation { .. } => {
+                format!("new {}(...)", self.node_type.to_string())
+            }
+            BoundNodeKind::FieldExpression { base, field } => {
+                format!("{}.{}", base.to_string(), field.identifier)
+            }
+            BoundNodeKind::FieldAssignmentExpression { base, field, value } => {
+                format!(
+                    "{}.{} = {}",
+                    base.to_string(),
+                    field.identifier,
+                    value.to_string()
+                )
+            }
+            BoundNodeKind::MethodCallExpression { base, method, .. } => {
+                format!("{}.{}(...)", base.to_string(), method.function.identifier)
+            }
             BoundNodeKind::ReferenceExpression(sym) => sym.identifier.clone(),
+            BoundNodeKind::ArrayLiteral { elements } => {
+                let elements_fmt: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+                format!("[{}]", elements_fmt.join(", "))
+            }
+            BoundNodeKind::IndexExpression { base, index } => {
+                format!("{}[{}]", base.to_string(), index.to_string())
+            }
+            BoundNodeKind::IndexAssignmentExpression { base, index, value } => {
+                format!(
+                    "{}[{}] = {}",
+                    base.to_string(),
+                    index.to_string(),
+                    value.to_string()
+                )
+            }
             BoundNodeKind::NumberLiteral(num) => num.to_string(),
+            BoundNodeKind::RealLiteral(num) => format!("{:?}", num),
             BoundNodeKind::BooleanLiteral(bool) => bool.to_string(),
             BoundNodeKind::StringLiteral(val) => {
                 format!("\\\"{}\\\"", val)
             }
+            BoundNodeKind::InterpolatedString { parts } => {
+                let parts_fmt: Vec<String> = parts
+                    .iter()
+                    .map(|part| match &part.kind {
+                        BoundNodeKind::StringLiteral(val) => val.clone(),
+                        _ => format!("{{{}}}", part.to_string()),
+                    })
+                    .collect();
+
+                format!("\\\"{}\\\"", parts_fmt.join(""))
+            }
         }
     }
 }
 
 #[derive(Debug)]
 pub enum BoundNodeKind {
+    // a node that failed to bind, the
+    // error was already reported
+    Error,
     Module {
+        // modules imported for the first time, they're
+        // evaluated before the module that imports them
+        imports: Vec<BoundNode>,
         block: Box<BoundNode>,
     },
     Block {
@@ -110,20 +296,33 @@ pub enum BoundNodeKind {
         block: Box<BoundNode>,
         else_block: Option<Box<BoundNode>>,
     },
+    CaseStatement {
+        expr: Box<BoundNode>,
+        arms: Vec<BoundCaseArm>,
+        otherwise: Option<Box<BoundNode>>,
+    },
     FunctionDeclaration {
         symbol: FunctionSymbol,
         block: Arc<BoundNode>,
     },
+    ClassDeclaration {
+        symbol: ClassSymbol,
+        initializers: Arc<Vec<BoundFieldInitializer>>,
+        methods: Vec<BoundNode>,
+    },
     ForLoop {
         iterator: VariableSymbol,
-        lower_bound: usize,
-        upper_bound: usize,
+        lower_bound: Box<BoundNode>,
+        upper_bound: Box<BoundNode>,
+        step: Option<Box<BoundNode>>,
         block: Arc<BoundNode>,
     },
     WhileLoop {
         expr: Box<BoundNode>,
         block: Arc<BoundNode>,
     },
+    BreakStatement,
+    ContinueStatement,
     BinaryExpression {
         lhs: Box<BoundNode>,
         op: Operator,
@@ -137,6 +336,9 @@ pub enum BoundNodeKind {
         symbol: VariableSymbol,
         value: Box<BoundNode>,
     },
+    VariableDeclaration {
+        symbol: VariableSymbol,
+    },
     BoundCallExpression {
         symbol: FunctionSymbol,
         args: Box<Vec<BoundNode>>,
@@ -146,14 +348,63 @@ pub enum BoundNodeKind {
         base: Box<BoundNode>,
         next: Box<BoundNode>,
     },
+    ClassInstantiation {
+        constructor: Option<MethodSymbol>,
+        args: Box<Vec<BoundNode>>,
+    },
+    FieldExpression {
+        base: Box<BoundNode>,
+        field: FieldSymbol,
+    },
+    FieldAssignmentExpression {
+        base: Box<BoundNode>,
+        field: FieldSymbol,
+        value: Box<BoundNode>,
+    },
+    MethodCallExpression {
+        base: Box<BoundNode>,
+        method: MethodSymbol,
+        args: Box<Vec<BoundNode>>,
+    },
     ReferenceExpression(VariableSymbol),
+    ArrayLiteral {
+        elements: Vec<BoundNode>,
+    },
+    IndexExpression {
+        base: Box<BoundNode>,
+        index: Box<BoundNode>,
+    },
+    IndexAssignmentExpression {
+        base: Box<BoundNode>,
+        index: Box<BoundNode>,
+        value: Box<BoundNode>,
+    },
     NumberLiteral(i64),
+    RealLiteral(f64),
     BooleanLiteral(bool),
     StringLiteral(String),
+    InterpolatedString {
+        parts: Vec<BoundNode>,
+    },
 }
 
 #[derive(Debug, Clone)]
 pub struct BoundParameter {
     pub symbol: VariableSymbol,
     pub param_type: TypeKind,
+    // the caller's variable is updated with the
+    // parameter's value when the call returns
+    pub is_ref: bool,
+}
+
+#[derive(Debug)]
+pub struct BoundFieldInitializer {
+    pub field: FieldSymbol,
+    pub value: BoundNode,
+}
+
+#[derive(Debug)]
+pub struct BoundCaseArm {
+    pub value: BoundNode,
+    pub block: BoundNode,
 }
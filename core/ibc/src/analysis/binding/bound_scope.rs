@@ -1,9 +1,14 @@
 use std::{cell::RefCell, rc::Rc};
 
+use crate::analysis::error_bag::ErrorKind;
+
 use super::{
     bound_node::BoundParameter,
-    symbols::{FunctionSymbol, GlobalSymbolScope, VariableSymbol},
-    types::TypeKind,
+    inference::TypeInference,
+    symbols::{
+        ClassSymbol, ConstantValue, FieldSymbol, FunctionSymbol, GlobalSymbolScope, VariableSymbol,
+    },
+    types::{ClassType, TypeKind},
 };
 
 #[derive(Debug)]
@@ -12,21 +17,56 @@ pub struct BoundScope {
     symbol_scope: Rc<RefCell<GlobalSymbolScope>>,
     variables: Vec<VariableSymbol>,
     functions: Vec<FunctionSymbol>,
+    classes: Vec<ClassType>,
+    in_loop: bool,
+    frame_depth: usize,
+    // the class whose members are being bound,
+    // its private members are accessible here
+    class_id: Option<u64>,
+    // the return type of the function being bound
+    ret_type: Option<TypeKind>,
+    inference: Rc<RefCell<TypeInference>>,
 }
 
 impl BoundScope {
     pub fn new(parent: Rc<RefCell<BoundScope>>) -> BoundScope {
         let sym_scope = parent.borrow().symbol_scope.clone();
+        let in_loop = parent.borrow().in_loop;
+        let frame_depth = parent.borrow().frame_depth;
+        let class_id = parent.borrow().class_id;
+        let ret_type = parent.borrow().ret_type.clone();
+        let inference = parent.borrow().inference.clone();
 
         BoundScope {
             parent: Some(parent),
             symbol_scope: sym_scope,
             variables: Vec::new(),
             functions: Vec::new(),
+            classes: Vec::new(),
+            in_loop: in_loop,
+            frame_depth: frame_depth,
+            class_id: class_id,
+            ret_type: ret_type,
+            inference: inference,
         }
     }
 
-    pub fn new_root() -> BoundScope {
+    pub fn new_loop(parent: Rc<RefCell<BoundScope>>) -> BoundScope {
+        let mut scope = BoundScope::new(parent);
+        scope.in_loop = true;
+        scope
+    }
+
+    pub fn new_function(parent: Rc<RefCell<BoundScope>>) -> BoundScope {
+        // loops don't carry over into function
+        // bodies declared inside of them
+        let mut scope = BoundScope::new(parent);
+        scope.in_loop = false;
+        scope.frame_depth += 1;
+        scope
+    }
+
+    pub fn new_root(inference: Rc<RefCell<TypeInference>>) -> BoundScope {
         let sym_scope = GlobalSymbolScope::new();
         let sym_scope_ref = Rc::new(RefCell::new(sym_scope));
 
@@ -35,33 +75,103 @@ impl BoundScope {
             symbol_scope: sym_scope_ref,
             variables: Vec::new(),
             functions: Vec::new(),
+            classes: Vec::new(),
+            in_loop: false,
+            frame_depth: 0,
+            class_id: None,
+            ret_type: None,
+            inference: inference,
         }
     }
 
+    pub fn new_class(parent: Rc<RefCell<BoundScope>>, class_id: u64) -> BoundScope {
+        let mut scope = BoundScope::new(parent);
+        scope.class_id = Some(class_id);
+        scope
+    }
+
+    pub fn is_in_loop(&self) -> bool {
+        self.in_loop
+    }
+
+    pub fn is_in_function(&self) -> bool {
+        self.frame_depth != 0
+    }
+
+    pub fn class_id(&self) -> Option<u64> {
+        self.class_id
+    }
+
+    pub fn set_ret_type(&mut self, ret_type: TypeKind) {
+        self.ret_type = Some(ret_type);
+    }
+
+    pub fn ret_type(&self) -> Option<TypeKind> {
+        self.ret_type.clone()
+    }
+
+    pub fn inference(&self) -> Rc<RefCell<TypeInference>> {
+        self.inference.clone()
+    }
+
     pub fn assign_variable(
         &mut self,
         identifier: String,
         var_type: TypeKind,
-    ) -> Option<VariableSymbol> {
+    ) -> Result<VariableSymbol, ErrorKind> {
         let existing = self.get_variable(identifier.clone());
         match existing {
             Some(symbol) => {
-                if symbol.var_type == var_type {
-                    Some(symbol)
+                if symbol.is_constant {
+                    Err(ErrorKind::CannotAssignToConstant(identifier))
+                } else if symbol.var_type.is_assignable_from(&var_type) {
+                    Ok(symbol)
                 } else {
-                    None
+                    Err(ErrorKind::AssignMismatchedTypes {
+                        expected: symbol.var_type,
+                        found: var_type,
+                    })
                 }
             }
             None => {
                 let mut sym_scope = self.symbol_scope.borrow_mut();
-                let symbol = sym_scope.alloc_variable(identifier, var_type);
+                let symbol = sym_scope.alloc_variable(identifier, var_type, self.frame_depth);
 
                 self.variables.push(symbol.clone());
-                Some(symbol)
+                Ok(symbol)
             }
         }
     }
 
+    pub fn declare_variable(&mut self, identifier: String, var_type: TypeKind) -> VariableSymbol {
+        // always a new variable, shadowing any
+        // variable with the same name
+        let mut sym_scope = self.symbol_scope.borrow_mut();
+        let symbol = sym_scope.alloc_variable(identifier, var_type, self.frame_depth);
+
+        self.variables.push(symbol.clone());
+        symbol
+    }
+
+    pub fn declare_constant(
+        &mut self,
+        identifier: String,
+        var_type: TypeKind,
+        value: Option<ConstantValue>,
+    ) -> VariableSymbol {
+        let mut symbol = self.declare_variable(identifier, var_type);
+        symbol.is_constant = true;
+        symbol.constant_value = value;
+
+        // the scope keeps the constant version
+        *self.variables.last_mut().unwrap() = symbol.clone();
+        symbol
+    }
+
+    pub fn has_local_variable(&self, identifier: &str) -> bool {
+        self.variables.iter().any(|v| v.identifier == identifier)
+    }
+
     pub fn get_variable(&self, identifier: String) -> Option<VariableSymbol> {
         let mut matching = self.variables.to_vec();
         matching.retain(|v| v.identifier == identifier);
@@ -88,7 +198,8 @@ impl BoundScope {
             Some(_) => return None,
             None => {
                 let mut sym_scope = self.symbol_scope.borrow_mut();
-                let symbol = sym_scope.alloc_function(identifier, params, ret_type);
+                let frame_depth = self.frame_depth + 1;
+                let symbol = sym_scope.alloc_function(identifier, params, ret_type, frame_depth);
 
                 self.functions.push(symbol.clone());
                 Some(symbol)
@@ -96,6 +207,22 @@ impl BoundScope {
         }
     }
 
+    pub fn import_function(&mut self, symbol: FunctionSymbol) -> bool {
+        // importing the same function twice is fine,
+        // any other function with its name is a conflict
+        match self.get_function(symbol.identifier.clone()) {
+            Some(f) => f.symbol_id == symbol.symbol_id,
+            None => {
+                self.functions.push(symbol);
+                true
+            }
+        }
+    }
+
+    pub fn local_functions(&self) -> Vec<FunctionSymbol> {
+        self.functions.to_vec()
+    }
+
     pub fn get_function(&self, identifier: String) -> Option<FunctionSymbol> {
         let mut matching = self.functions.to_vec();
         matching.retain(|f| f.identifier == identifier);
@@ -110,4 +237,77 @@ impl BoundScope {
             Some(def.clone())
         }
     }
+
+    pub fn alloc_method(
+        &self,
+        identifier: String,
+        params: Vec<BoundParameter>,
+        ret_type: TypeKind,
+    ) -> FunctionSymbol {
+        // methods are looked up through their class,
+        // so they aren't added to the scope
+        let mut sym_scope = self.symbol_scope.borrow_mut();
+        let frame_depth = self.frame_depth + 1;
+        sym_scope.alloc_function(identifier, params, ret_type, frame_depth)
+    }
+
+    pub fn alloc_field(
+        &self,
+        identifier: String,
+        field_type: TypeKind,
+        owner_id: u64,
+        is_private: bool,
+    ) -> FieldSymbol {
+        let mut sym_scope = self.symbol_scope.borrow_mut();
+        sym_scope.alloc_field(identifier, field_type, owner_id, is_private)
+    }
+
+    pub fn declare_class(&mut self, identifier: String) -> Option<ClassType> {
+        let existing = self.get_class(identifier.clone());
+        match existing {
+            Some(_) => return None,
+            None => {
+                let mut sym_scope = self.symbol_scope.borrow_mut();
+                let class_type = sym_scope.alloc_class(identifier);
+
+                self.classes.push(class_type.clone());
+                Some(class_type)
+            }
+        }
+    }
+
+    pub fn set_class_parent(&mut self, class_type: &ClassType, parent: &ClassType) -> ClassType {
+        let class_type = self
+            .symbol_scope
+            .borrow_mut()
+            .set_class_parent(class_type, parent);
+
+        for c in self.classes.iter_mut() {
+            if c.class_id == class_type.class_id {
+                *c = class_type.clone();
+            }
+        }
+
+        class_type
+    }
+
+    pub fn define_class(&self, symbol: ClassSymbol) {
+        self.symbol_scope.borrow_mut().define_class(symbol);
+    }
+
+    pub fn get_class(&self, identifier: String) -> Option<ClassType> {
+        let class_type = self.classes.iter().find(|c| c.identifier == identifier);
+
+        match class_type {
+            Some(c) => Some(c.clone()),
+            None => match &self.parent {
+                Some(parent) => parent.borrow().get_class(identifier),
+                None => None,
+            },
+        }
+    }
+
+    pub fn get_class_symbol(&self, class_type: &ClassType) -> ClassSymbol {
+        self.symbol_scope.borrow().get_class(class_type)
+    }
 }
@@ -0,0 +1,379 @@
+use std::collections::HashMap;
+
+use crate::analysis::{
+    error_bag::ErrorKind,
+    span::{FileId, Span},
+};
+
+use super::types::TypeKind;
+
+// parameters and functions are found by their location,
+// which is the same every time the program is bound
+type SpanKey = (FileId, usize, usize);
+
+fn span_key(span: &Span) -> SpanKey {
+    (span.start.file, span.start.line, span.start.col)
+}
+
+#[derive(Debug)]
+struct TypeVariable {
+    // set once the variable is unified with another
+    // variable, which then holds the inferred type
+    link: Option<usize>,
+    inferred: Option<TypeKind>,
+    // the first use that doesn't fit the inferred type
+    conflict: Option<TypeKind>,
+    is_return: bool,
+    // a return statement returned a value, even if
+    // its type couldn't be found
+    returns_value: bool,
+    // where the types were found, only known for returns
+    inferred_span: Option<Span>,
+    conflict_span: Option<Span>,
+}
+
+// the type of one variable flows into another,
+// e.g. a returned parameter or a call passed as an argument
+#[derive(Debug)]
+struct TypeEdge {
+    to: usize,
+    from: usize,
+    span: Option<Span>,
+}
+
+// an untyped value that is indexed into is an array,
+// its elements are another variable
+#[derive(Debug)]
+struct ElementOf {
+    array: usize,
+    element: usize,
+}
+
+// parameters without a type annotation and functions without
+// a return type are bound as type variables first, every use
+// of a parameter, every call site and every return statement
+// then tells us more about them. the program is bound again
+// with the inferred types
+#[derive(Debug)]
+pub struct TypeInference {
+    variables: Vec<TypeVariable>,
+    edges: Vec<TypeEdge>,
+    elements: Vec<ElementOf>,
+    params: HashMap<SpanKey, usize>,
+    returns: HashMap<SpanKey, usize>,
+    first_pass: bool,
+    // return types are still being inferred, the
+    // parameters are already known by now
+    resolving_returns: bool,
+    // a type was found since the last pass ended
+    changed: bool,
+}
+
+impl TypeInference {
+    pub fn new() -> Self {
+        TypeInference {
+            variables: Vec::new(),
+            edges: Vec::new(),
+            elements: Vec::new(),
+            params: HashMap::new(),
+            returns: HashMap::new(),
+            first_pass: true,
+            resolving_returns: false,
+            changed: false,
+        }
+    }
+
+    pub fn has_variables(&self) -> bool {
+        self.variables.len() != 0
+    }
+
+    pub fn end_first_pass(&mut self) {
+        self.propagate();
+        self.first_pass = false;
+        self.resolving_returns = true;
+        self.changed = false;
+    }
+
+    pub fn end_return_pass(&mut self) -> bool {
+        // returns can depend on the inferred parameters, e.g.
+        // a field of one, so the program is bound with them
+        // until no return type changes
+        self.propagate();
+        let changed = self.changed;
+        self.changed = false;
+        changed
+    }
+
+    pub fn end_inference(&mut self) {
+        self.resolving_returns = false;
+    }
+
+    fn propagate(&mut self) {
+        // keep following the edges until no type changes,
+        // types only ever widen so this always ends
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for i in 0..self.edges.len() {
+                let from = self.find(self.edges[i].from);
+                let Some(inferred) = self.variables[from].inferred.clone() else {
+                    continue;
+                };
+
+                let span = self.edges[i].span.clone();
+                changed |= self.constrain(self.edges[i].to, inferred, span);
+            }
+
+            for i in 0..self.elements.len() {
+                changed |= self.infer_element(i);
+            }
+        }
+    }
+
+    pub fn param_type(&mut self, identifier: String, span: &Span) -> Result<TypeKind, ErrorKind> {
+        if self.first_pass {
+            let var = self.new_variable(false);
+            self.params.insert(span_key(span), var);
+            Ok(TypeKind::Infer(var))
+        } else {
+            self.inferred_param(identifier, span)
+        }
+    }
+
+    pub fn return_type(
+        &mut self,
+        identifier: String,
+        span: &Span,
+    ) -> Result<TypeKind, Vec<(ErrorKind, Span)>> {
+        if self.first_pass {
+            let var = self.new_variable(true);
+            self.returns.insert(span_key(span), var);
+            Ok(TypeKind::Infer(var))
+        } else if self.resolving_returns {
+            match self.returns.get(&span_key(span)) {
+                Some(var) => Ok(TypeKind::Infer(*var)),
+                None => Ok(TypeKind::Void),
+            }
+        } else {
+            self.inferred_return(identifier, span)
+        }
+    }
+
+    fn new_variable(&mut self, is_return: bool) -> usize {
+        let variable = TypeVariable {
+            link: None,
+            inferred: None,
+            conflict: None,
+            is_return: is_return,
+            returns_value: false,
+            inferred_span: None,
+            conflict_span: None,
+        };
+
+        self.variables.push(variable);
+        self.variables.len() - 1
+    }
+
+    fn inferred_param(&self, identifier: String, span: &Span) -> Result<TypeKind, ErrorKind> {
+        let var = match self.params.get(&span_key(span)) {
+            Some(v) => &self.variables[self.find(*v)],
+            None => return Err(ErrorKind::CannotInferParamType(identifier)),
+        };
+
+        match (&var.inferred, &var.conflict) {
+            (Some(first), Some(second)) => Err(ErrorKind::ConflictingParamTypes {
+                param: identifier,
+                first: first.clone(),
+                second: second.clone(),
+            }),
+            (Some(inferred), None) => Ok(inferred.clone()),
+            (None, _) => Err(ErrorKind::CannotInferParamType(identifier)),
+        }
+    }
+
+    fn inferred_return(
+        &self,
+        identifier: String,
+        span: &Span,
+    ) -> Result<TypeKind, Vec<(ErrorKind, Span)>> {
+        let var = match self.returns.get(&span_key(span)) {
+            Some(v) => &self.variables[self.find(*v)],
+            None => return Ok(TypeKind::Void),
+        };
+
+        match (&var.inferred, &var.conflict) {
+            (Some(first), Some(second)) => {
+                // both returns are pointed out
+                let conflict = ErrorKind::ConflictingReturnTypes {
+                    function: identifier.clone(),
+                    first: first.clone(),
+                    second: second.clone(),
+                };
+
+                let inferred = ErrorKind::InferredReturnType {
+                    function: identifier,
+                    ret_type: first.clone(),
+                };
+
+                let conflict_span = var.conflict_span.clone().unwrap_or(span.clone());
+                let inferred_span = var.inferred_span.clone().unwrap_or(span.clone());
+                Err(vec![(conflict, conflict_span), (inferred, inferred_span)])
+            }
+            (Some(inferred), None) => Ok(inferred.clone()),
+            // values are returned, but none of them has a known type
+            (None, _) if var.returns_value => Err(vec![(
+                ErrorKind::CannotInferReturnType(identifier),
+                span.clone(),
+            )]),
+            // nothing is returned
+            (None, _) => Ok(TypeKind::Void),
+        }
+    }
+
+    pub fn element_type(&mut self, array: usize) -> TypeKind {
+        // indexing only tells us the value is an array,
+        // the element type is found from how it's used
+        if !self.first_pass {
+            return TypeKind::Error;
+        }
+
+        let element = self.new_variable(false);
+        let element_of = ElementOf {
+            array: array,
+            element: element,
+        };
+
+        self.elements.push(element_of);
+        TypeKind::Infer(element)
+    }
+
+    fn infer_element(&mut self, i: usize) -> bool {
+        let array = self.find(self.elements[i].array);
+        let element = self.find(self.elements[i].element);
+
+        match (
+            &self.variables[array].inferred,
+            &self.variables[element].inferred,
+        ) {
+            (Some(TypeKind::Array(element_type)), _) => {
+                let element_type = *element_type.clone();
+                self.constrain(element, element_type, None)
+            }
+            // return types are only inferred from returns
+            (None, Some(element_type)) if !self.variables[array].is_return => {
+                let array_type = TypeKind::Array(Box::new(element_type.clone()));
+                self.constrain(array, array_type, None)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn unify(&mut self, lhs: &TypeKind, rhs: &TypeKind) {
+        match (lhs, rhs) {
+            (TypeKind::Infer(lhs), TypeKind::Infer(rhs)) => self.unify_variables(*lhs, *rhs),
+            (TypeKind::Infer(var), other) | (other, TypeKind::Infer(var)) => {
+                // expressions without a value tell us nothing,
+                // and return types are only inferred from returns
+                if other != &TypeKind::Void && !self.variables[*var].is_return {
+                    self.constrain(*var, other.clone(), None);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn unify_variables(&mut self, lhs: usize, rhs: usize) {
+        // a parameter used with the result of a call
+        // has to accept whatever the call returns
+        match (self.variables[lhs].is_return, self.variables[rhs].is_return) {
+            (false, false) => self.union(lhs, rhs),
+            (false, true) => self.add_edge(lhs, rhs, None),
+            (true, false) => self.add_edge(rhs, lhs, None),
+            (true, true) => {}
+        }
+    }
+
+    pub fn infer_return(&mut self, var: usize, returned: &TypeKind, span: &Span) {
+        if returned != &TypeKind::Void {
+            self.variables[var].returns_value = true;
+        }
+
+        match returned {
+            TypeKind::Infer(returned) => self.add_edge(var, *returned, Some(span.clone())),
+            returned => {
+                self.constrain(var, returned.clone(), Some(span.clone()));
+            }
+        }
+    }
+
+    fn add_edge(&mut self, to: usize, from: usize, span: Option<Span>) {
+        let edge = TypeEdge {
+            to: to,
+            from: from,
+            span: span,
+        };
+
+        self.edges.push(edge);
+    }
+
+    fn find(&self, var: usize) -> usize {
+        let mut var = var;
+        while let Some(link) = self.variables[var].link {
+            var = link;
+        }
+
+        var
+    }
+
+    fn union(&mut self, lhs: usize, rhs: usize) {
+        let lhs = self.find(lhs);
+        let rhs = self.find(rhs);
+        if lhs == rhs {
+            return;
+        }
+
+        self.variables[rhs].link = Some(lhs);
+        if let Some(inferred) = self.variables[rhs].inferred.take() {
+            self.constrain(lhs, inferred, None);
+        }
+
+        if let Some(conflict) = self.variables[rhs].conflict.take() {
+            self.constrain(lhs, conflict, None);
+        }
+    }
+
+    fn constrain(&mut self, var: usize, found: TypeKind, span: Option<Span>) -> bool {
+        // errors were already reported
+        if found.is_error() {
+            return false;
+        }
+
+        let var = self.find(var);
+        let variable = &mut self.variables[var];
+        let Some(inferred) = &variable.inferred else {
+            variable.inferred = Some(found);
+            variable.inferred_span = span;
+            self.changed = true;
+            return true;
+        };
+
+        // the variable has to accept every type found,
+        // so ints and reals are inferred as reals
+        if inferred.is_assignable_from(&found) {
+            return false;
+        }
+
+        if found.is_assignable_from(inferred) {
+            variable.inferred = Some(found);
+            variable.inferred_span = span;
+        } else if variable.conflict.is_none() {
+            variable.conflict = Some(found);
+            variable.conflict_span = span;
+        } else {
+            return false;
+        }
+
+        self.changed = true;
+        true
+    }
+}
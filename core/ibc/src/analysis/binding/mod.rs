@@ -1,20 +1,83 @@
 use std::{cell::RefCell, rc::Rc};
 
-use super::{error_bag::ErrorBag, syntax::syntax_token::SyntaxToken};
+use super::{
+    control_flow,
+    error_bag::ErrorBag,
+    syntax::syntax_token::{SyntaxKind, SyntaxToken},
+};
 
 pub mod binder;
 pub mod bound_node;
 mod bound_scope;
 mod builtin_functions;
+pub mod inference;
+pub mod modules;
 pub mod symbols;
 pub mod types;
 
-pub fn bind_root(root: &SyntaxToken, errors: &mut ErrorBag) -> Option<bound_node::BoundNode> {
-    // yes there will be two root scopes, but this is
-    // just a minor inefficiency
-
-    let mut scope = bound_scope::BoundScope::new_root();
+fn bind_program(
+    root: &SyntaxToken,
+    name: Option<String>,
+    resolver: modules::ModuleResolver,
+    inference: Rc<RefCell<inference::TypeInference>>,
+    errors: &mut ErrorBag,
+) -> bound_node::BoundNode {
+    let mut scope = bound_scope::BoundScope::new_root(inference);
     builtin_functions::declare_builtin_functions(&mut scope);
 
-    binder::bind(root, Rc::new(RefCell::new(scope)), errors)
+    let mut loader = modules::ModuleLoader::new(name, resolver, Rc::new(RefCell::new(scope)));
+    let SyntaxKind::Module { imports, block, .. } = &root.kind else {
+        unreachable!()
+    };
+
+    let scope = loader.module_scope();
+    let (module, _) = binder::bind_module(imports, block, scope, &mut loader, errors, root.span);
+    module
+}
+
+pub fn bind_root(
+    root: &SyntaxToken,
+    name: Option<String>,
+    resolver: modules::ModuleResolver,
+    errors: &mut ErrorBag,
+) -> bound_node::BoundNode {
+    // the first pass collects how untyped parameters are used and
+    // what functions without a return type return, its errors are
+    // only kept if there was nothing to infer
+    let inference = Rc::new(RefCell::new(inference::TypeInference::new()));
+    let mut first_pass = ErrorBag::new();
+    first_pass.files = errors.files.clone();
+
+    let module = bind_program(
+        root,
+        name.clone(),
+        resolver,
+        inference.clone(),
+        &mut first_pass,
+    );
+    if !inference.borrow().has_variables() {
+        errors.errors.append(&mut first_pass.errors);
+        errors.files = first_pass.files;
+        return module;
+    }
+
+    control_flow::infer_return_types(&module, &mut inference.borrow_mut());
+    inference.borrow_mut().end_first_pass();
+
+    // bound with the inferred parameters until
+    // the return types are known too
+    loop {
+        let mut pass = ErrorBag::new();
+        pass.files = errors.files.clone();
+
+        let module = bind_program(root, name.clone(), resolver, inference.clone(), &mut pass);
+        control_flow::infer_return_types(&module, &mut inference.borrow_mut());
+        if !inference.borrow_mut().end_return_pass() {
+            break;
+        }
+    }
+
+    // bound again with the inferred types
+    inference.borrow_mut().end_inference();
+    bind_program(root, name, resolver, inference, errors)
 }
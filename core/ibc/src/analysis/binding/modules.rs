@@ -0,0 +1,108 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::analysis::{
+    error_bag::{ErrorBag, ErrorKind},
+    span::Span,
+    syntax::{self, syntax_token::SyntaxKind},
+};
+
+use super::{binder, bound_node::BoundNode, bound_scope::BoundScope, symbols::FunctionSymbol};
+
+// loads the contents of an imported module by its
+// name, None when there's no module with that name
+pub type ModuleResolver<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+pub struct ModuleLoader<'a> {
+    resolver: ModuleResolver<'a>,
+    // the scope with the builtin functions, every
+    // module is bound in its own child of it
+    root: Rc<RefCell<BoundScope>>,
+    // modules that are being bound, importing
+    // one of them again would be a cycle. the
+    // main file is bound first, so it's always here
+    loading: Vec<String>,
+    // the functions exported by every bound module
+    loaded: HashMap<String, Vec<FunctionSymbol>>,
+}
+
+impl<'a> ModuleLoader<'a> {
+    pub fn new(
+        main: Option<String>,
+        resolver: ModuleResolver<'a>,
+        root: Rc<RefCell<BoundScope>>,
+    ) -> Self {
+        let loading = match main {
+            Some(main) => vec![module_key(&main)],
+            None => Vec::new(),
+        };
+
+        ModuleLoader {
+            resolver: resolver,
+            root: root,
+            loading: loading,
+            loaded: HashMap::new(),
+        }
+    }
+
+    pub fn module_scope(&self) -> Rc<RefCell<BoundScope>> {
+        let scope = BoundScope::new(self.root.clone());
+        Rc::new(RefCell::new(scope))
+    }
+
+    pub fn load(
+        &mut self,
+        module: &String,
+        errors: &mut ErrorBag,
+        span: Span,
+    ) -> Option<(Vec<FunctionSymbol>, Option<BoundNode>)> {
+        // a module is only bound the first time it's imported,
+        // later imports just get its exported functions
+        let key = module_key(module);
+        if let Some(exports) = self.loaded.get(&key) {
+            return Some((exports.clone(), None));
+        }
+
+        if self.loading.contains(&key) {
+            errors.add(ErrorKind::CyclicImport(module.clone()), span);
+            return None;
+        }
+
+        let contents = match (self.resolver)(module) {
+            Some(c) => c,
+            None => {
+                errors.add(ErrorKind::ModuleNotFound(module.clone()), span);
+                return None;
+            }
+        };
+
+        let file = errors.add_file(module.clone());
+        let num_errors = errors.errors.len();
+        let root = syntax::parse(contents, file, errors);
+        let has_syntax_errors = errors.errors.len() != num_errors;
+        let SyntaxKind::Module { imports, block, .. } = &root.kind else {
+            unreachable!()
+        };
+
+        self.loading.push(key.clone());
+        let scope = self.module_scope();
+        let (node, exports) = binder::bind_module(imports, block, scope, self, errors, root.span);
+        self.loading.pop();
+
+        // like the main file, a module with syntax errors is
+        // still bound for diagnostics, but it can't be run
+        let node = if has_syntax_errors {
+            BoundNode::error(span)
+        } else {
+            node
+        };
+
+        self.loaded.insert(key, exports.clone());
+        Some((exports, Some(node)))
+    }
+}
+
+// modules can be imported with or without
+// the .ib extension, both are the same module
+fn module_key(module: &str) -> String {
+    module.strip_suffix(".ib").unwrap_or(module).to_string()
+}
@@ -1,22 +1,41 @@
-use super::{bound_node::BoundParameter, types::TypeKind};
+use std::collections::HashMap;
+
+use super::{
+    bound_node::BoundParameter,
+    types::{ClassType, TypeKind},
+};
 
 #[derive(Debug)]
 pub struct GlobalSymbolScope {
     num_symbols: u64,
+    // classes by id, so their members can be looked
+    // up from anywhere a value of the class is used
+    classes: HashMap<u64, ClassSymbol>,
 }
 
 impl GlobalSymbolScope {
     pub fn new() -> GlobalSymbolScope {
-        GlobalSymbolScope { num_symbols: 0 }
+        GlobalSymbolScope {
+            num_symbols: 0,
+            classes: HashMap::new(),
+        }
     }
 
-    pub fn alloc_variable(&mut self, identifier: String, var_type: TypeKind) -> VariableSymbol {
+    pub fn alloc_variable(
+        &mut self,
+        identifier: String,
+        var_type: TypeKind,
+        frame_depth: usize,
+    ) -> VariableSymbol {
         self.num_symbols += 1;
 
         VariableSymbol {
             identifier: identifier,
             var_type: var_type,
             symbol_id: self.num_symbols,
+            frame_depth: frame_depth,
+            is_constant: false,
+            constant_value: None,
         }
     }
 
@@ -25,6 +44,7 @@ impl GlobalSymbolScope {
         identifier: String,
         parameters: Vec<BoundParameter>,
         ret_type: TypeKind,
+        frame_depth: usize,
     ) -> FunctionSymbol {
         self.num_symbols += 1;
 
@@ -33,8 +53,73 @@ impl GlobalSymbolScope {
             parameters: parameters,
             ret_type: ret_type,
             symbol_id: self.num_symbols,
+            frame_depth: frame_depth,
         }
     }
+
+    pub fn alloc_field(
+        &mut self,
+        identifier: String,
+        field_type: TypeKind,
+        owner_id: u64,
+        is_private: bool,
+    ) -> FieldSymbol {
+        self.num_symbols += 1;
+
+        FieldSymbol {
+            identifier: identifier,
+            field_type: field_type,
+            symbol_id: self.num_symbols,
+            owner_id: owner_id,
+            is_private: is_private,
+        }
+    }
+
+    pub fn alloc_class(&mut self, identifier: String) -> ClassType {
+        self.num_symbols += 1;
+
+        let class_type = ClassType {
+            identifier: identifier,
+            class_id: self.num_symbols,
+            ancestors: Vec::new(),
+        };
+
+        let symbol = ClassSymbol {
+            class_type: class_type.clone(),
+            parent: None,
+            fields: Vec::new(),
+            methods: Vec::new(),
+            constructor: None,
+        };
+
+        self.classes.insert(class_type.class_id, symbol);
+        class_type
+    }
+
+    pub fn define_class(&mut self, symbol: ClassSymbol) {
+        let id = symbol.class_type.class_id;
+        self.classes.insert(id, symbol);
+    }
+
+    pub fn set_class_parent(&mut self, class_type: &ClassType, parent: &ClassType) -> ClassType {
+        let mut ancestors = vec![parent.class_id];
+        ancestors.extend(parent.ancestors.iter());
+
+        let class_type = ClassType {
+            identifier: class_type.identifier.clone(),
+            class_id: class_type.class_id,
+            ancestors: ancestors,
+        };
+
+        let symbol = self.classes.get_mut(&class_type.class_id).unwrap();
+        symbol.class_type = class_type.clone();
+        symbol.parent = Some(parent.clone());
+        class_type
+    }
+
+    pub fn get_class(&self, class_type: &ClassType) -> ClassSymbol {
+        self.classes[&class_type.class_id].clone()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -42,6 +127,41 @@ pub struct VariableSymbol {
     pub identifier: String,
     pub var_type: TypeKind,
     pub symbol_id: u64,
+    // number of function bodies the variable is
+    // declared in, 0 for globals
+    pub frame_depth: usize,
+    pub is_constant: bool,
+    // constants initialized with a literal are
+    // folded into their uses by the binder
+    pub constant_value: Option<ConstantValue>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstantValue {
+    Int(i64),
+    Real(f64),
+    Boolean(bool),
+    String(String),
+}
+
+impl ConstantValue {
+    // formatted like the evaluator formats values
+    pub fn to_string(&self) -> String {
+        match self {
+            ConstantValue::Int(v) => v.to_string(),
+            ConstantValue::Real(v) => format!("{:?}", v),
+            ConstantValue::Boolean(v) => v.to_string(),
+            ConstantValue::String(v) => v.clone(),
+        }
+    }
+
+    pub fn as_real(&self) -> Option<f64> {
+        match self {
+            ConstantValue::Int(v) => Some(*v as f64),
+            ConstantValue::Real(v) => Some(*v),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -50,4 +170,80 @@ pub struct FunctionSymbol {
     pub parameters: Vec<BoundParameter>,
     pub ret_type: TypeKind,
     pub symbol_id: u64,
+    // the frame depth of the function body
+    pub frame_depth: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldSymbol {
+    pub identifier: String,
+    pub field_type: TypeKind,
+    pub symbol_id: u64,
+    // the class that declared the field
+    pub owner_id: u64,
+    pub is_private: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct MethodSymbol {
+    pub function: FunctionSymbol,
+    // the instance the method is called on, it's
+    // assigned in the method's frame like a parameter
+    pub this: VariableSymbol,
+    // the class that declared the method
+    pub owner_id: u64,
+    pub is_private: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClassSymbol {
+    pub class_type: ClassType,
+    pub parent: Option<ClassType>,
+    // inherited members come first, overriding
+    // methods replace the ones they override
+    pub fields: Vec<FieldSymbol>,
+    pub methods: Vec<MethodSymbol>,
+    pub constructor: Option<MethodSymbol>,
+}
+
+impl FunctionSymbol {
+    pub fn signature(&self) -> String {
+        // e.g. swap(ref a: Int, ref b: Int) -> Void
+        let params: Vec<String> = self
+            .parameters
+            .iter()
+            .map(|p| {
+                let modifier = if p.is_ref { "ref " } else { "" };
+                format!(
+                    "{}{}: {}",
+                    modifier,
+                    p.symbol.identifier,
+                    p.param_type.to_string()
+                )
+            })
+            .collect();
+
+        format!(
+            "{}({}) -> {}",
+            self.identifier,
+            params.join(", "),
+            self.ret_type.to_string()
+        )
+    }
+}
+
+impl ClassSymbol {
+    pub fn get_field(&self, identifier: &str) -> Option<&FieldSymbol> {
+        self.fields.iter().find(|f| f.identifier == identifier)
+    }
+
+    pub fn get_method(&self, identifier: &str) -> Option<&MethodSymbol> {
+        self.methods
+            .iter()
+            .find(|m| m.function.identifier == identifier)
+    }
+
+    pub fn has_member(&self, identifier: &str) -> bool {
+        self.get_field(identifier).is_some() || self.get_method(identifier).is_some()
+    }
 }
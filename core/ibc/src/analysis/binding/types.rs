@@ -1,21 +1,50 @@
+use std::collections::HashMap;
+
 use crate::{
     analysis::{
         error_bag::{ErrorBag, ErrorKind},
         span::Span,
+        syntax::syntax_token::TypeSyntax,
     },
-    eval::evaluator::EvalValue,
+    eval::evaluator::{EvalValue, RuntimeError},
 };
 
+use super::bound_scope::BoundScope;
+
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub enum TypeKind {
     Void,
     Int,
+    Real,
     String,
     Boolean,
     Array(Box<TypeKind>),
     Collection(Box<TypeKind>),
     Stack(Box<TypeKind>),
     Queue(Box<TypeKind>),
+    Class(ClassType),
+    // the type of a parameter without a type annotation
+    // while it's being inferred, see `inference`
+    Infer(usize),
+    // the type of an expression that failed to bind
+    Error,
+}
+
+// a user declared class, its members are
+// looked up through the class symbol
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct ClassType {
+    pub identifier: String,
+    pub class_id: u64,
+    // ids of the parent class and its parents,
+    // nearest first
+    pub ancestors: Vec<u64>,
+}
+
+impl ClassType {
+    pub fn is_subclass_of(&self, other: &ClassType) -> bool {
+        self.class_id == other.class_id || self.ancestors.contains(&other.class_id)
+    }
 }
 
 pub struct TypeMethodRepresentation {
@@ -34,6 +63,7 @@ impl TypeKind {
         match &self {
             TypeKind::Void => "Void".to_string(),
             TypeKind::Int => "Int".to_string(),
+            TypeKind::Real => "Real".to_string(),
             TypeKind::String => "String".to_string(),
             TypeKind::Boolean => "Boolean".to_string(),
             TypeKind::Array(generic) => {
@@ -52,6 +82,63 @@ impl TypeKind {
                 let generic = generic.to_string();
                 format!("Queue<{}>", generic)
             }
+            TypeKind::Class(class_type) => class_type.identifier.clone(),
+            TypeKind::Infer(_) => "Unknown".to_string(),
+            TypeKind::Error => "Error".to_string(),
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        match self {
+            TypeKind::Int | TypeKind::Real => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_primitive(&self) -> bool {
+        match self {
+            TypeKind::Int | TypeKind::Real | TypeKind::String | TypeKind::Boolean => true,
+            _ => false,
+        }
+    }
+
+    pub fn generic(&self) -> Option<&TypeKind> {
+        match self {
+            TypeKind::Array(generic)
+            | TypeKind::Collection(generic)
+            | TypeKind::Stack(generic)
+            | TypeKind::Queue(generic) => Some(generic),
+            _ => None,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        match self {
+            TypeKind::Error => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_inferred(&self) -> bool {
+        match self {
+            TypeKind::Infer(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_assignable_from(&self, other: &TypeKind) -> bool {
+        // ints are widened to reals, errors were
+        // already reported so they match anything
+        match (self, other) {
+            (TypeKind::Real, TypeKind::Int) => true,
+            (TypeKind::Error, _) | (_, TypeKind::Error) => true,
+            // constraints on inferred types are
+            // recorded by the binder instead
+            (TypeKind::Infer(_), _) | (_, TypeKind::Infer(_)) => true,
+            // objects of a subclass can be used wherever
+            // an object of its parent class is expected
+            (TypeKind::Class(target), TypeKind::Class(value)) => value.is_subclass_of(target),
+            _ => self == other,
         }
     }
 
@@ -221,84 +308,66 @@ impl TypeKind {
     }
 }
 
+fn get_generic_type(
+    type_syntax: &TypeSyntax,
+    scope: &BoundScope,
+    errors: &mut ErrorBag,
+) -> Option<TypeKind> {
+    match &type_syntax.generic {
+        Some(generic) => get_type(generic, scope, errors),
+        None => {
+            let kind = ErrorKind::ExpectsGenericTypeParam(type_syntax.identifier.clone());
+            errors.add(kind, type_syntax.span);
+            None
+        }
+    }
+}
+
 pub fn get_type(
-    identifier: String,
-    generic: Option<String>,
-    span: &Span,
+    type_syntax: &TypeSyntax,
+    scope: &BoundScope,
     errors: &mut ErrorBag,
 ) -> Option<TypeKind> {
+    let identifier = type_syntax.identifier.clone();
     let type_kind = match identifier.as_str() {
         "Void" => TypeKind::Void,
         "Int" => TypeKind::Int,
+        "Real" => TypeKind::Real,
         "String" => TypeKind::String,
         "Boolean" => TypeKind::Boolean,
-        "Array" => {
-            let generic = match generic {
-                Some(id) => match get_type(id, None, span, errors) {
-                    Some(t) => t,
-                    None => return None,
-                },
-                None => {
-                    let kind = ErrorKind::ExpectsGenericTypeParam("Array".to_string());
-                    errors.add(kind, span.clone());
-                    return None;
-                }
-            };
-
-            TypeKind::Array(Box::new(generic))
-        }
-        "Collection" => {
-            let generic = match generic {
-                Some(id) => match get_type(id, None, span, errors) {
-                    Some(t) => t,
-                    None => return None,
-                },
-                None => {
-                    let kind = ErrorKind::ExpectsGenericTypeParam("Collection".to_string());
-                    errors.add(kind, span.clone());
-                    return None;
-                }
-            };
-
-            TypeKind::Collection(Box::new(generic))
-        }
-        "Stack" => {
-            let generic = match generic {
-                Some(id) => match get_type(id, None, span, errors) {
-                    Some(t) => t,
-                    None => return None,
-                },
-                None => {
-                    let kind = ErrorKind::ExpectsGenericTypeParam("Stack".to_string());
-                    errors.add(kind, span.clone());
-                    return None;
-                }
-            };
-
-            TypeKind::Stack(Box::new(generic))
-        }
-        "Queue" => {
-            let generic = match generic {
-                Some(id) => match get_type(id, None, span, errors) {
-                    Some(t) => t,
-                    None => return None,
-                },
-                None => {
-                    let kind = ErrorKind::ExpectsGenericTypeParam("Queue".to_string());
-                    errors.add(kind, span.clone());
-                    return None;
-                }
-            };
-
-            TypeKind::Queue(Box::new(generic))
-        }
-        _ => {
-            let kind = ErrorKind::UndefinedType(identifier);
-            errors.add(kind, span.clone());
-            return None;
-        }
+        "Array" => match get_generic_type(type_syntax, scope, errors) {
+            Some(t) => TypeKind::Array(Box::new(t)),
+            None => return None,
+        },
+        "Collection" => match get_generic_type(type_syntax, scope, errors) {
+            Some(t) => TypeKind::Collection(Box::new(t)),
+            None => return None,
+        },
+        "Stack" => match get_generic_type(type_syntax, scope, errors) {
+            Some(t) => TypeKind::Stack(Box::new(t)),
+            None => return None,
+        },
+        "Queue" => match get_generic_type(type_syntax, scope, errors) {
+            Some(t) => TypeKind::Queue(Box::new(t)),
+            None => return None,
+        },
+        _ => match scope.get_class(identifier.clone()) {
+            Some(class_type) => TypeKind::Class(class_type),
+            None => {
+                let kind = ErrorKind::UndefinedType(identifier);
+                errors.add(kind, type_syntax.span);
+                return None;
+            }
+        },
     };
 
+    // only the collection types above take a generic
+    if type_kind.generic().is_none() && type_syntax.generic.is_some() {
+        let kind = ErrorKind::UnexpectedGenericTypeParam(identifier);
+        errors.add(kind, type_syntax.span);
+        return None;
+    }
+
     Some(type_kind)
 }
 
@@ -308,6 +377,7 @@ pub enum ObjectState {
     Collection(CollectionState),
     Stack(StackState),
     Queue(QueueState),
+    Instance(InstanceState),
 }
 
 #[derive(Debug, Clone)]
@@ -321,6 +391,35 @@ impl ArrayState {
             internal: Vec::new(),
         }
     }
+
+    pub fn from_values(values: Vec<EvalValue>) -> Self {
+        ArrayState { internal: values }
+    }
+
+    pub fn get(&self, index: i64, span: Span) -> Result<EvalValue, RuntimeError> {
+        let index = self.check_index(index, span)?;
+        Ok(self.internal[index].clone())
+    }
+
+    pub fn set(&mut self, index: i64, value: EvalValue, span: Span) -> Result<(), RuntimeError> {
+        let index = self.check_index(index, span)?;
+        self.internal[index] = value;
+        Ok(())
+    }
+
+    fn check_index(&self, index: i64, span: Span) -> Result<usize, RuntimeError> {
+        let len = self.internal.len();
+        if index < 0 || index as usize >= len {
+            let message = format!(
+                "Index {} is out of range for an array of length {}",
+                index, len
+            );
+
+            return Err(RuntimeError::new(message, span));
+        }
+
+        Ok(index as usize)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -364,6 +463,25 @@ impl QueueState {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct InstanceState {
+    // the class the object was created from,
+    // methods are dispatched on it
+    pub class_id: u64,
+    // field values by field symbol id, fields
+    // without an initializer start unassigned
+    pub fields: HashMap<u64, EvalValue>,
+}
+
+impl InstanceState {
+    pub fn new(class_id: u64) -> Self {
+        InstanceState {
+            class_id: class_id,
+            fields: HashMap::new(),
+        }
+    }
+}
+
 pub fn get_object_state(tp: TypeKind) -> ObjectState {
     match tp {
         TypeKind::Array(_) => ObjectState::Array(ArrayState::new()),
@@ -39,7 +39,7 @@ fn analyze_func_rec(
             };
 
             // we can return since next node is end
-            if ret_type == func_ret_type {
+            if func_ret_type.is_assignable_from(ret_type) {
                 return;
             }
 
@@ -72,6 +72,48 @@ pub fn analyze_func(
     func_ret_type: &TypeKind,
     errors: &mut ErrorBag,
 ) {
+    // the return type couldn't be bound,
+    // which was already reported
+    if func_ret_type.is_error() {
+        return;
+    }
+
     // check if all paths return a value
     analyze_func_rec(root, loc, func_ret_type, errors);
 }
+
+fn collect_returns_rec(
+    node: Rc<RefCell<ControlFlowNode>>,
+    visited: &mut Vec<Rc<RefCell<ControlFlowNode>>>,
+    returns: &mut Vec<(TypeKind, Span)>,
+) {
+    // paths join again after every if, so
+    // each node is only looked at once
+    if visited.iter().any(|v| Rc::ptr_eq(v, &node)) {
+        return;
+    }
+
+    visited.push(node.clone());
+
+    let node = node.borrow();
+    if let (Some(ret_type), Some(ret_span)) = (&node.ret_type, &node.ret_span) {
+        returns.push((ret_type.clone(), ret_span.clone()));
+    }
+
+    if let Some(on_condition) = &node.on_condition {
+        collect_returns_rec(on_condition.clone(), visited, returns);
+    }
+
+    if let Some(next) = &node.next {
+        collect_returns_rec(next.clone(), visited, returns);
+    }
+}
+
+pub fn collect_returns(root: Rc<RefCell<ControlFlowNode>>) -> Vec<(TypeKind, Span)> {
+    // every reachable return, in the order they're written
+    let mut visited: Vec<Rc<RefCell<ControlFlowNode>>> = Vec::new();
+    let mut returns: Vec<(TypeKind, Span)> = Vec::new();
+    collect_returns_rec(root, &mut visited, &mut returns);
+
+    returns
+}
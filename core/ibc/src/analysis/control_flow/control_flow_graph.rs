@@ -1,8 +1,11 @@
 use std::{cell::RefCell, rc::Rc};
 
-use crate::analysis::binding::{
-    bound_node::{BoundNode, BoundNodeKind},
-    types::TypeKind,
+use crate::analysis::{
+    binding::{
+        bound_node::{BoundNode, BoundNodeKind},
+        types::TypeKind,
+    },
+    span::Span,
 };
 
 use super::FuncControlFlow;
@@ -13,6 +16,7 @@ pub struct ControlFlowNode {
     pub next: Option<Rc<RefCell<ControlFlowNode>>>,
     pub on_condition: Option<Rc<RefCell<ControlFlowNode>>>,
     pub ret_type: Option<TypeKind>,
+    pub ret_span: Option<Span>,
     graph_label: String,
     graph_id: String,
 }
@@ -45,6 +49,7 @@ impl ControlFlowNode {
             next: None,
             on_condition: None,
             ret_type: None,
+            ret_span: None,
             graph_label: label,
             graph_id: count.to_string(),
         }
@@ -152,6 +157,7 @@ fn walk(
 
             new_node.next = Some(end_node);
             new_node.ret_type = Some(node.node_type.clone());
+            new_node.ret_span = Some(node.span.clone());
 
             let node_ref = Rc::new(RefCell::new(new_node));
             (node_ref.clone(), node_ref)
@@ -192,6 +198,73 @@ fn walk(
             let if_ref = Rc::new(RefCell::new(if_node));
             (if_ref, end_if_ref)
         }
+        BoundNodeKind::CaseStatement {
+            expr: _,
+            arms,
+            otherwise,
+        } => {
+            let case_node = ControlFlowNode::new(counter.clone(), node.to_string());
+            let case_ref = Rc::new(RefCell::new(case_node));
+            let end_case_node = ControlFlowNode::new(counter.clone(), "end case".to_string());
+            let end_case_ref = Rc::new(RefCell::new(end_case_node));
+
+            // every arm is a condition, when it doesn't match
+            // we move on to the next arm, just like else if
+            let mut prev_arm = case_ref.clone();
+            for arm in arms.iter() {
+                let label = format!("when {}", arm.value.to_string());
+                let mut arm_node = ControlFlowNode::new(counter.clone(), label);
+
+                let arm_span = walk(&arm.block, None, end_node.clone(), counter.clone());
+                arm_node.on_condition = Some(arm_span.first);
+
+                let mut arm_last = arm_span.last.borrow_mut();
+                if let None = arm_last.next {
+                    // this arm doesn't explicitly return,
+                    // thus we connect it to the end case
+                    arm_last.next = Some(end_case_ref.clone());
+                }
+
+                let arm_ref = Rc::new(RefCell::new(arm_node));
+                prev_arm.borrow_mut().next = Some(arm_ref.clone());
+                prev_arm = arm_ref;
+            }
+
+            // no arm matched
+            match otherwise {
+                Some(o) => {
+                    let span = walk(&o, Some(prev_arm), end_node.clone(), counter.clone());
+
+                    let mut last = span.last.borrow_mut();
+                    if let None = last.next {
+                        last.next = Some(end_case_ref.clone());
+                    }
+                }
+                None => prev_arm.borrow_mut().next = Some(end_case_ref.clone()),
+            };
+
+            (case_ref, end_case_ref)
+        }
+        BoundNodeKind::ForLoop { block, .. } | BoundNodeKind::WhileLoop { block, .. } => {
+            // the body might not run at all,
+            // just like an if without an else
+            let mut loop_node = ControlFlowNode::new(counter.clone(), node.to_string());
+            let end_loop_node = ControlFlowNode::new(counter.clone(), "end loop".to_string());
+            let end_loop_ref = Rc::new(RefCell::new(end_loop_node));
+
+            let body_span = walk(&block, None, end_node.clone(), counter.clone());
+            loop_node.on_condition = Some(body_span.first);
+
+            let mut body_last = body_span.last.borrow_mut();
+            if let None = body_last.next {
+                body_last.next = Some(end_loop_ref.clone());
+            }
+
+            loop_node.next = Some(end_loop_ref.clone());
+
+            let loop_ref = Rc::new(RefCell::new(loop_node));
+            (loop_ref, end_loop_ref)
+        }
         _ => {
             let node = ControlFlowNode::new(counter, node.to_string());
             let node_ref = Rc::new(RefCell::new(node));
@@ -5,6 +5,7 @@ use self::control_flow_graph::ControlFlowNode;
 use super::{
     binding::{
         bound_node::{BoundNode, BoundNodeKind},
+        inference::TypeInference,
         types::TypeKind,
     },
     error_bag::ErrorBag,
@@ -26,7 +27,11 @@ fn scan_for_functions_recursive(
     functions: &mut Vec<FuncControlFlow>,
 ) {
     match &node.kind {
-        BoundNodeKind::Module { block } => {
+        BoundNodeKind::Module { imports, block } => {
+            for import in imports.iter() {
+                scan_for_functions_recursive(import, errors, functions);
+            }
+
             scan_for_functions_recursive(&block, errors, functions);
         }
         BoundNodeKind::Block { children } => {
@@ -44,6 +49,22 @@ fn scan_for_functions_recursive(
                 scan_for_functions_recursive(e, errors, functions)
             }
         }
+        BoundNodeKind::CaseStatement {
+            expr: _,
+            arms,
+            otherwise,
+        } => {
+            for arm in arms.iter() {
+                scan_for_functions_recursive(&arm.block, errors, functions);
+            }
+
+            if let Some(o) = otherwise {
+                scan_for_functions_recursive(o, errors, functions)
+            }
+        }
+        BoundNodeKind::ForLoop { block, .. } | BoundNodeKind::WhileLoop { block, .. } => {
+            scan_for_functions_recursive(block, errors, functions);
+        }
         BoundNodeKind::FunctionDeclaration { symbol, block } => {
             let func = FuncControlFlow {
                 block: block.clone(),
@@ -52,6 +73,12 @@ fn scan_for_functions_recursive(
             };
 
             functions.push(func);
+            scan_for_functions_recursive(block, errors, functions);
+        }
+        BoundNodeKind::ClassDeclaration { methods, .. } => {
+            for method in methods.iter() {
+                scan_for_functions_recursive(method, errors, functions);
+            }
         }
         _ => {}
     }
@@ -86,3 +113,22 @@ pub fn analyze(root: &BoundNode, errors: &mut ErrorBag) -> Vec<Rc<RefCell<Contro
 
     graphs
 }
+
+pub fn infer_return_types(root: &BoundNode, inference: &mut TypeInference) {
+    // functions without a return type return whatever
+    // their reachable return statements return
+    let mut errors = ErrorBag::new();
+    let mut function_declarations: Vec<FuncControlFlow> = Vec::new();
+    scan_for_functions_recursive(root, &mut errors, &mut function_declarations);
+
+    for func in function_declarations {
+        let TypeKind::Infer(ret_var) = func.ret_type else {
+            continue;
+        };
+
+        let graph = control_flow_graph::contruct_graph(func);
+        for (ret_type, span) in control_flow_analyzer::collect_returns(graph) {
+            inference.infer_return(ret_var, &ret_type, &span);
+        }
+    }
+}
@@ -1,6 +1,17 @@
-use super::{binding::types::TypeKind, operator::Operator, span::Span};
+use super::{
+    binding::types::TypeKind,
+    operator::Operator,
+    span::{FileId, Span, MAIN_FILE},
+};
 
 pub enum ErrorKind {
+    // Lexing Errors
+    UnterminatedString,
+    UnterminatedComment,
+    InvalidEscapeSequence(String),
+    UnexpectedCharacter(char),
+    IntegerOverflow(String),
+
     // Parsing Errors
     ExpectedToken(String),
     ExpectedScope,
@@ -13,15 +24,59 @@ pub enum ErrorKind {
     ExpectedLoop,
     ExpectedLoopLowerBound,
     ExpectedLoopUpperBound,
+    ExpectedLoopStep,
+    UnexpectedToken(String),
+    UnexpectedEndOfFile,
 
     // Binding Errors
-    FailedParsing,
     NumberParsing,
-    AssignMismatchedTypes,
-    ParamMismatchedTypes(String),
+    AssignMismatchedTypes {
+        expected: TypeKind,
+        found: TypeKind,
+    },
+    VariableAlreadyDeclared(String),
+    DuplicateParameter(String),
+    CannotInferParamType(String),
+    ConflictingParamTypes {
+        param: String,
+        first: TypeKind,
+        second: TypeKind,
+    },
+    CannotInferReturnType(String),
+    ConflictingReturnTypes {
+        function: String,
+        first: TypeKind,
+        second: TypeKind,
+    },
+    InferredReturnType {
+        function: String,
+        ret_type: TypeKind,
+    },
     CannotFindValue(String),
     CannotFindFunction(String),
     CannotDeclareFunction(String),
+    CannotDeclareClass(String),
+    ClassInsideFunction(String),
+    DuplicateClassMember {
+        class: String,
+        member: String,
+    },
+    UnknownClassMember {
+        class: String,
+        member: String,
+    },
+    PrivateClassMember {
+        class: String,
+        member: String,
+    },
+    MismatchedOverride {
+        class: String,
+        method: String,
+    },
+    CyclicInheritance(String),
+    CannotAssignToConstant(String),
+    ModuleNotFound(String),
+    CyclicImport(String),
     MismatchedNumberOfArgs {
         id: String,
         expected: usize,
@@ -32,6 +87,10 @@ pub enum ErrorKind {
         expected: TypeKind,
         found: TypeKind,
     },
+    RefArgNotAssignable {
+        id: String,
+        param: String,
+    },
     NotAllCodePathsReturn,
     ReturnTypeMismatch {
         found: TypeKind,
@@ -53,11 +112,37 @@ pub enum ErrorKind {
         rhs: TypeKind,
     },
     ExpectsGenericTypeParam(String),
+    UnexpectedGenericTypeParam(String),
+    CannotInterpolateType(TypeKind),
+    CannotOutputType(TypeKind),
+    CannotInferArrayType,
+    ArrayElementMismatchedTypes {
+        expected: TypeKind,
+        found: TypeKind,
+    },
+    CannotIndexType(TypeKind),
+    IndexMustBeInt(TypeKind),
+    BreakOutsideLoop,
+    ContinueOutsideLoop,
+    LoopBoundMustBeInt(TypeKind),
+    LoopStepCannotBeZero,
+    CaseNotDefinedOnType(TypeKind),
+    CaseArmMismatchedTypes {
+        expected: TypeKind,
+        found: TypeKind,
+    },
+    DuplicateCaseArm,
 }
 
 impl ErrorKind {
     pub fn format(&self) -> String {
         match self {
+            Self::UnterminatedString => "Unterminated string literal".to_string(),
+            Self::UnterminatedComment => "Unterminated block comment".to_string(),
+            Self::InvalidEscapeSequence(escape) => format!("Invalid escape sequence '{}'", escape),
+            Self::UnexpectedCharacter(c) => format!("Unexpected character '{}'", c),
+            Self::IntegerOverflow(value) => format!("Integer literal {} is too large", value),
+
             Self::ExpectedToken(token) => format!("Expected token: {}", token),
             Self::ExpectedScope => "Expected scope".to_string(),
             Self::UnknownOperator(op) => format!("{} is not an operator", op),
@@ -69,45 +154,107 @@ impl ErrorKind {
             Self::ExpectedLoop => "Expected loop".to_string(),
             Self::ExpectedLoopLowerBound => "Expected loop lower bound".to_string(),
             Self::ExpectedLoopUpperBound => "Expected loop upper bound".to_string(),
+            Self::ExpectedLoopStep => "Expected loop step".to_string(),
+            Self::UnexpectedToken(token) => format!("Unexpected token: {}", token),
+            Self::UnexpectedEndOfFile => "Unexpected end of file".to_string(),
 
-            Self::FailedParsing => "Failed parsing".to_string(),
             Self::NumberParsing => "Cannot parse number".to_string(),
-            Self::AssignMismatchedTypes => "Mismatched types in assign expression".to_string(),
-            Self::ParamMismatchedTypes(param) => format!("Cannot assign parameter '{}' because a value with a different type already exists in the current scope", param),
+            Self::AssignMismatchedTypes { expected, found } => format!("Cannot assign a value of type {} to a variable of type {}", found.to_string(), expected.to_string()),
+            Self::VariableAlreadyDeclared(id) => format!("Variable '{}' is already declared in the current scope", id),
+            Self::DuplicateParameter(param) => format!("Parameter '{}' is already declared", param),
+            Self::CannotInferParamType(param) => format!("Cannot infer the type of parameter '{}', add a type annotation", param),
+            Self::ConflictingParamTypes { param, first, second } => format!("Parameter '{}' is used as both {} and {}, add a type annotation", param, first.to_string(), second.to_string()),
+            Self::CannotInferReturnType(function) => format!("Cannot infer the return type of '{}', add a return type", function),
+            Self::ConflictingReturnTypes { function, first, second } => format!("Function '{}' returns both {} and {}, add a return type", function, first.to_string(), second.to_string()),
+            Self::InferredReturnType { function, ret_type } => format!("The return type of '{}' was inferred as {} from this return", function, ret_type.to_string()),
             Self::CannotFindValue(id) => format!("Cannot find value '{}' in the current scope", id),
             Self::CannotFindFunction(id) => format!("Cannot find function '{}' in the current scope", id),
             Self::CannotDeclareFunction(id) => format!("Cannot declare function '{}' because the scope already contains one with the same name", id),
+            Self::CannotDeclareClass(id) => format!("Cannot declare class '{}' because the scope already contains one with the same name", id),
+            Self::ClassInsideFunction(id) => format!("Class '{}' cannot be declared inside a function, declare it at the top of the file", id),
+            Self::DuplicateClassMember { class, member } => format!("Class '{}' already has a member named '{}'", class, member),
+            Self::UnknownClassMember { class, member } => format!("Class '{}' has no member named '{}'", class, member),
+            Self::PrivateClassMember { class, member } => format!("Member '{}' of class '{}' is private", member, class),
+            Self::MismatchedOverride { class, method } => format!("Method '{}' of class '{}' doesn't match the signature of the method it overrides", method, class),
+            Self::CyclicInheritance(id) => format!("Class '{}' cannot extend itself", id),
+            Self::CannotAssignToConstant(id) => format!("Cannot assign to '{}' because it's a constant", id),
+            Self::ModuleNotFound(module) => format!("Cannot find module '{}'", module),
+            Self::CyclicImport(module) => format!("Module '{}' cannot be imported because it imports this file", module),
             Self::MismatchedNumberOfArgs { id, expected, found } => format!("Expected {} arguments, found {} when calling function '{}'", expected, found, id),
-            Self::MismatchedArgTypes { id, expected, found } => format!("Expected an argument of type {:?}, found {:?} when calling function {}", expected, found, id),
+            Self::MismatchedArgTypes { id, expected, found } => format!("Expected an argument of type {}, found {} when calling function {}", expected.to_string(), found.to_string(), id),
+            Self::RefArgNotAssignable { id, param } => format!("The argument for ref parameter '{}' must be a variable, field or array element when calling function {}", param, id),
             Self::NotAllCodePathsReturn => "Not all code paths return".to_string(),
-            Self::ReturnTypeMismatch { found, expected } => format!("Return type doesn't match function return type, found {:?}, expected {:?}", found, expected),
+            Self::ReturnTypeMismatch { found, expected } => format!("Return type doesn't match function return type, found {}, expected {}", found.to_string(), expected.to_string()),
             Self::ConditionMustBeBoolean(cond_type) => {
-                format!("Condition type must be boolean, found {:?}", cond_type)
+                format!("Condition type must be boolean, found {}", cond_type.to_string())
             }
             Self::UndefinedType(type_ref) => {
                 format!("Undefined type '{}' in the current scope", type_ref)
             }
             Self::UnaryOperatorNotDefinedOnType { op, used_type } => {
                 format!(
-                    "Unary operator '{:?}' not defined on type: '{:?}'",
-                    op, used_type
+                    "Unary operator '{:?}' not defined on type: '{}'",
+                    op,
+                    used_type.to_string()
                 )
             }
             Self::BinaryOPeratorNotDefinedOnType { op, lhs, rhs } => {
                 format!(
-                    "Binary operator '{:?}' not defined on types '{:?}' and '{:?}'",
-                    op, rhs, lhs
+                    "Binary operator '{:?}' not defined on types '{}' and '{}'",
+                    op,
+                    rhs.to_string(),
+                    lhs.to_string()
                 )
             }
             Self::EqualityNonMatchingTypes { lhs, rhs } => {
                 format!(
-                    "Equality operator must have matching types, found '{:?}' and '{:?}'",
-                    lhs, rhs
+                    "Equality operator must have matching types, found '{}' and '{}'",
+                    lhs.to_string(),
+                    rhs.to_string()
                 )
             },
             Self::ExpectsGenericTypeParam(t) => {
                 format!("Type {} expects a generic type parameter", t)
             }
+            Self::UnexpectedGenericTypeParam(t) => {
+                format!("Type {} doesn't take a generic type parameter", t)
+            }
+            Self::CannotInterpolateType(t) => {
+                format!("Values of type {} cannot be interpolated into a string", t.to_string())
+            }
+            Self::CannotOutputType(t) => {
+                format!("Values of type {} cannot be output", t.to_string())
+            }
+            Self::CannotInferArrayType => {
+                "Cannot infer the element type of an empty array, use new Array<T>() instead"
+                    .to_string()
+            }
+            Self::ArrayElementMismatchedTypes { expected, found } => {
+                format!(
+                    "Array elements must have the same type, expected {}, found {}",
+                    expected.to_string(),
+                    found.to_string()
+                )
+            }
+            Self::CannotIndexType(t) => format!("Cannot index into a value of type {}", t.to_string()),
+            Self::IndexMustBeInt(t) => format!("Index must be of type Int, found {}", t.to_string()),
+            Self::BreakOutsideLoop => "Cannot use break outside of a loop".to_string(),
+            Self::ContinueOutsideLoop => "Cannot use continue outside of a loop".to_string(),
+            Self::LoopBoundMustBeInt(bound_type) => {
+                format!("Loop bounds and step must be of type Int, found {}", bound_type.to_string())
+            }
+            Self::LoopStepCannotBeZero => "Loop step cannot be zero".to_string(),
+            Self::CaseNotDefinedOnType(t) => {
+                format!("Case statements can't match values of type {}", t.to_string())
+            }
+            Self::CaseArmMismatchedTypes { expected, found } => {
+                format!(
+                    "Case arm must match the type of the case expression, expected {}, found {}",
+                    expected.to_string(),
+                    found.to_string()
+                )
+            }
+            Self::DuplicateCaseArm => "This value is already handled by an earlier arm".to_string(),
         }
     }
 }
@@ -129,12 +276,28 @@ impl Error {
 
 pub struct ErrorBag {
     pub errors: Vec<Error>,
+    // names of the imported files, the
+    // main file has no name
+    pub files: Vec<String>,
 }
 
 impl ErrorBag {
     pub fn new() -> ErrorBag {
         ErrorBag {
             errors: Vec::<Error>::new(),
+            files: Vec::new(),
+        }
+    }
+
+    pub fn add_file(&mut self, name: String) -> FileId {
+        self.files.push(name);
+        self.files.len()
+    }
+
+    pub fn file_name(&self, file: FileId) -> Option<&String> {
+        match file {
+            MAIN_FILE => None,
+            _ => self.files.get(file - 1),
         }
     }
 
@@ -149,8 +312,12 @@ impl ErrorBag {
 
     pub fn report(&self) {
         for err in &self.errors {
-            let message = err.format();
-            print!("ERR: {}", message);
+            let message = match self.file_name(err.span.file()) {
+                Some(f) => format!("{} in file '{}'", err.format(), f),
+                None => err.format(),
+            };
+
+            println!("ERR: {}", message);
         }
     }
 }
@@ -6,7 +6,9 @@ pub mod span;
 pub mod syntax;
 
 use self::binding::bound_node::BoundNode;
-use self::error_bag::{ErrorBag, ErrorKind};
+use self::binding::modules::ModuleResolver;
+use self::error_bag::ErrorBag;
+use self::span::MAIN_FILE;
 
 pub struct AnalysisResult {
     pub errors: ErrorBag,
@@ -29,24 +31,28 @@ impl AnalysisResult {
     }
 }
 
-pub fn analyze(contents: String) -> AnalysisResult {
+// the name of the main file is used to report
+// modules that import it as cyclic imports
+pub fn analyze(contents: String, name: Option<String>, resolver: ModuleResolver) -> AnalysisResult {
     // parsing
     let mut bag = ErrorBag::new();
-    let root = match syntax::parse(contents, &mut bag) {
-        Some(root) => root,
-        None => {
-            bag.add(ErrorKind::FailedParsing, span::Span::new(0, 0, 0, 0, 0, 0));
-            return AnalysisResult::new_err(bag);
-        }
-    };
+    let root = syntax::parse(contents, MAIN_FILE, &mut bag);
+    let has_syntax_errors = bag.errors.len() != 0;
 
     // print!("{:#?}", &root);
 
     // binding
-    let bound = match binding::bind_root(&root, &mut bag) {
-        Some(bound_root) => bound_root,
-        None => return AnalysisResult::new_err(bag),
-    };
+    // nodes that failed to bind are replaced with
+    // error nodes, so every semantic error is reported,
+    // imported modules are parsed and bound along the way
+    let bound = binding::bind_root(&root, name, resolver, &mut bag);
+
+    if has_syntax_errors {
+        // the healthy parts of the tree were still
+        // bound for diagnostics, but a partially parsed
+        // program shouldn't be analyzed further or run
+        return AnalysisResult::new_err(bag);
+    }
 
     // println!("{:#?}", bound);
 
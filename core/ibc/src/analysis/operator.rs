@@ -11,8 +11,15 @@ pub enum Operator {
     Multiplication,
     Not,
     Equality,
+    Inequality,
     LesserThan,
     GreaterThan,
+    LesserThanOrEqual,
+    GreaterThanOrEqual,
+    IntegerDivision,
+    Modulo,
+    And,
+    Or,
 }
 
 impl Operator {
@@ -34,7 +41,7 @@ impl Operator {
                 return Some(TypeKind::Boolean);
             }
             Operator::Subtraction => {
-                if rhs_type != TypeKind::Int {
+                if !rhs_type.is_numeric() {
                     let err = ErrorKind::UnaryOperatorNotDefinedOnType {
                         op: self.clone(),
                         used_type: rhs_type,
@@ -44,7 +51,7 @@ impl Operator {
                     return None;
                 }
 
-                return Some(TypeKind::Int);
+                return Some(rhs_type);
             }
             _ => unreachable!("Operator {:?}", self),
         }
@@ -62,7 +69,7 @@ impl Operator {
         let span = rhs.span.clone();
         match self {
             Operator::Subtraction | Operator::Multiplication | Operator::Division => {
-                if rhs_type != TypeKind::Int || lhs_type != TypeKind::Int {
+                if !rhs_type.is_numeric() || !lhs_type.is_numeric() {
                     let err = ErrorKind::BinaryOPeratorNotDefinedOnType {
                         op: self.clone(),
                         lhs: lhs_type,
@@ -73,13 +80,15 @@ impl Operator {
                     return None;
                 }
 
-                return Some(TypeKind::Int);
+                return Some(Operator::numeric_result(&lhs_type, &rhs_type));
             }
             Operator::Addition => {
-                if rhs_type == TypeKind::String || lhs_type == TypeKind::String {
+                // only primitives can be joined onto a string
+                let strings = rhs_type == TypeKind::String || lhs_type == TypeKind::String;
+                if strings && rhs_type.is_primitive() && lhs_type.is_primitive() {
                     return Some(TypeKind::String);
-                } else if rhs_type == TypeKind::Int && lhs_type == TypeKind::Int {
-                    return Some(TypeKind::Int);
+                } else if rhs_type.is_numeric() && lhs_type.is_numeric() {
+                    return Some(Operator::numeric_result(&lhs_type, &rhs_type));
                 }
 
                 let err = ErrorKind::BinaryOPeratorNotDefinedOnType {
@@ -91,8 +100,9 @@ impl Operator {
                 errors.add(err, span);
                 None
             }
-            Operator::Equality => {
-                if rhs_type != lhs_type {
+            Operator::Equality | Operator::Inequality => {
+                let numeric = rhs_type.is_numeric() && lhs_type.is_numeric();
+                if rhs_type != lhs_type && !numeric {
                     let err = ErrorKind::EqualityNonMatchingTypes {
                         lhs: lhs_type,
                         rhs: rhs_type,
@@ -104,7 +114,27 @@ impl Operator {
 
                 Some(TypeKind::Boolean)
             }
-            Operator::LesserThan | Operator::GreaterThan => {
+            Operator::LesserThan
+            | Operator::GreaterThan
+            | Operator::LesserThanOrEqual
+            | Operator::GreaterThanOrEqual => {
+                // strings are compared lexicographically
+                let numeric = rhs_type.is_numeric() && lhs_type.is_numeric();
+                let strings = rhs_type == TypeKind::String && lhs_type == TypeKind::String;
+                if !numeric && !strings {
+                    let err = ErrorKind::BinaryOPeratorNotDefinedOnType {
+                        op: self.clone(),
+                        lhs: lhs_type,
+                        rhs: rhs_type,
+                    };
+
+                    errors.add(err, span);
+                    return None;
+                }
+
+                return Some(TypeKind::Boolean);
+            }
+            Operator::IntegerDivision | Operator::Modulo => {
                 if rhs_type != TypeKind::Int || lhs_type != TypeKind::Int {
                     let err = ErrorKind::BinaryOPeratorNotDefinedOnType {
                         op: self.clone(),
@@ -116,12 +146,35 @@ impl Operator {
                     return None;
                 }
 
+                return Some(TypeKind::Int);
+            }
+            Operator::And | Operator::Or => {
+                if rhs_type != TypeKind::Boolean || lhs_type != TypeKind::Boolean {
+                    let err = ErrorKind::BinaryOPeratorNotDefinedOnType {
+                        op: self.clone(),
+                        lhs: lhs_type,
+                        rhs: rhs_type,
+                    };
+
+                    errors.add(err, span);
+                    return None;
+                }
+
                 return Some(TypeKind::Boolean);
             }
             _ => unreachable!(),
         }
     }
 
+    fn numeric_result(lhs: &TypeKind, rhs: &TypeKind) -> TypeKind {
+        // mixing ints and reals widens to real
+        if *lhs == TypeKind::Real || *rhs == TypeKind::Real {
+            TypeKind::Real
+        } else {
+            TypeKind::Int
+        }
+    }
+
     pub fn to_string(&self) -> String {
         let op = match &self {
             Operator::Addition => "+",
@@ -130,8 +183,15 @@ impl Operator {
             Operator::Multiplication => "*",
             Operator::Not => "!",
             Operator::Equality => "==",
+            Operator::Inequality => "!=",
             Operator::LesserThan => "<",
             Operator::GreaterThan => ">",
+            Operator::LesserThanOrEqual => "<=",
+            Operator::GreaterThanOrEqual => ">=",
+            Operator::IntegerDivision => "div",
+            Operator::Modulo => "mod",
+            Operator::And => "AND",
+            Operator::Or => "OR",
         };
 
         op.to_string()
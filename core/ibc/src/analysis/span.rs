@@ -1,8 +1,14 @@
+// files are numbered in the order they're loaded,
+// the file being analyzed is always the main file
+pub type FileId = usize;
+pub const MAIN_FILE: FileId = 0;
+
 #[derive(Debug, Clone, Copy)]
 pub struct Location {
     pub line: usize,
     pub col: usize,
     pub char_offset: usize,
+    pub file: FileId,
 }
 
 impl Location {
@@ -11,6 +17,7 @@ impl Location {
             line: line,
             col: col,
             char_offset: char_offset,
+            file: MAIN_FILE,
         }
     }
 }
@@ -52,4 +59,13 @@ impl Span {
             end: end,
         }
     }
+
+    pub fn file(&self) -> FileId {
+        self.start.file
+    }
+
+    pub fn set_file(&mut self, file: FileId) {
+        self.start.file = file;
+        self.end.file = file;
+    }
 }
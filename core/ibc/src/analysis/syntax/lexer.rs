@@ -1,11 +1,18 @@
-use std::{fmt, iter::Peekable, str::Chars, usize, vec};
+use std::{fmt, iter::Peekable, num::IntErrorKind, str::Chars, usize, vec};
 
-use crate::analysis::span::{Location, Span};
+use crate::analysis::{
+    error_bag::{ErrorBag, ErrorKind},
+    span::{FileId, Location, Span},
+};
 
 #[derive(Debug)]
 pub struct LexerToken {
     pub kind: LexerTokenKind,
     pub span: Span,
+    // comments are not passed to the parser, they
+    // are kept on the surrounding tokens instead
+    pub leading_trivia: Vec<Trivia>,
+    pub trailing_trivia: Vec<Trivia>,
 }
 
 impl LexerToken {
@@ -13,11 +20,46 @@ impl LexerToken {
         LexerToken {
             kind: kind,
             span: span,
+            leading_trivia: Vec::new(),
+            trailing_trivia: Vec::new(),
         }
     }
+
+    // a generic list closed right before an assignment,
+    // i.e. `x: Array<Int>= y`, is lexed as `>=`. the
+    // parser splits it into `>` and `=`
+    pub fn split_greater_than_equals(&self) -> (LexerToken, LexerToken) {
+        let mut middle = self.span.start;
+        middle.col += 1;
+        middle.char_offset += 1;
+
+        let greater_span = Span::from_loc(self.span.start, middle);
+        let mut greater = LexerToken::new(LexerTokenKind::GreaterThanToken, greater_span);
+        greater.leading_trivia = self.leading_trivia.clone();
+
+        let equals_span = Span::from_loc(middle, self.span.end);
+        let mut equals = LexerToken::new(LexerTokenKind::EqualsToken, equals_span);
+        equals.trailing_trivia = self.trailing_trivia.clone();
+
+        (greater, equals)
+    }
+}
+
+// comments are kept with their text, so a formatter
+// or doc hover can recover them
+#[derive(Debug, Clone)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub span: Span,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone)]
+pub enum TriviaKind {
+    LineComment(String),
+    BlockComment(String),
+}
+
+#[derive(Debug, PartialEq)]
 pub enum LexerTokenKind {
     PlusToken,
     MinusToken,
@@ -28,15 +70,27 @@ pub enum LexerTokenKind {
     ArrowToken,
     GreaterThanToken,
     LesserThanToken,
+    GreaterThanEqualsToken,
+    LesserThanEqualsToken,
     EqualsEqualsToken,
+    BangEqualsToken,
     OpenParenthesisToken,
     CloseParenthesisToken,
+    OpenBracketToken,
+    CloseBracketToken,
     CommaToken,
     ColonToken,
     DotToken,
     IntegerLiteralToken(i64),
+    RealLiteralToken(f64),
     IdentifierToken(String),
     StringLiteralToken(String),
+    // string literals with embedded expressions are split
+    // at the braces, i.e. "a {x} b {y} c" is lexed as
+    // start("a ") x middle(" b ") y end(" c")
+    InterpolationStartToken(String),
+    InterpolationMiddleToken(String),
+    InterpolationEndToken(String),
 
     IfKeyword,
     ThenKeyword,
@@ -51,35 +105,97 @@ pub enum LexerTokenKind {
     LoopKeyword,
     FromKeyword,
     ToKeyword,
+    StepKeyword,
     WhileKeyword,
+    BreakKeyword,
+    ContinueKeyword,
+    DivKeyword,
+    ModKeyword,
+    AndKeyword,
+    OrKeyword,
+    NotKeyword,
+    ClassKeyword,
+    ImportKeyword,
+    ConstructorKeyword,
+    ExtendsKeyword,
+    PrivateKeyword,
+    ConstantKeyword,
+    CaseKeyword,
+    OfKeyword,
+    WhenKeyword,
+    OtherwiseKeyword,
+    RefKeyword,
 }
 
 impl LexerTokenKind {
     pub fn unary_operator_precedence(&self) -> usize {
         match self {
-            LexerTokenKind::PlusToken => 4,
-            LexerTokenKind::MinusToken => 4,
-            LexerTokenKind::BangToken => 4,
+            LexerTokenKind::PlusToken => 7,
+            LexerTokenKind::MinusToken => 7,
+            LexerTokenKind::BangToken => 7,
+
+            // binds looser than comparisons, so
+            // NOT a == b is NOT (a == b)
+            LexerTokenKind::NotKeyword => 2,
             _ => 0,
         }
     }
 
     pub fn binary_operator_precedence(&self) -> usize {
         match self {
-            LexerTokenKind::StarToken => 4,
-            LexerTokenKind::SlashToken => 4,
+            LexerTokenKind::StarToken => 6,
+            LexerTokenKind::SlashToken => 6,
+            LexerTokenKind::DivKeyword => 6,
+            LexerTokenKind::ModKeyword => 6,
+
+            LexerTokenKind::PlusToken => 5,
+            LexerTokenKind::MinusToken => 5,
 
-            LexerTokenKind::PlusToken => 3,
-            LexerTokenKind::MinusToken => 3,
+            LexerTokenKind::GreaterThanToken => 4,
+            LexerTokenKind::LesserThanToken => 4,
+            LexerTokenKind::GreaterThanEqualsToken => 4,
+            LexerTokenKind::LesserThanEqualsToken => 4,
 
-            LexerTokenKind::GreaterThanToken => 2,
-            LexerTokenKind::LesserThanToken => 2,
+            LexerTokenKind::EqualsEqualsToken => 3,
+            LexerTokenKind::BangEqualsToken => 3,
 
-            LexerTokenKind::EqualsEqualsToken => 1,
+            LexerTokenKind::AndKeyword => 2,
+
+            LexerTokenKind::OrKeyword => 1,
 
             _ => 0,
         }
     }
+
+    pub fn is_statement_start(&self) -> bool {
+        match self {
+            LexerTokenKind::OutputKeyword
+            | LexerTokenKind::IfKeyword
+            | LexerTokenKind::ReturnKeyword
+            | LexerTokenKind::FunctionKeyword
+            | LexerTokenKind::ClassKeyword
+            | LexerTokenKind::ConstructorKeyword
+            | LexerTokenKind::PrivateKeyword
+            | LexerTokenKind::ConstantKeyword
+            | LexerTokenKind::ImportKeyword
+            | LexerTokenKind::CaseKeyword
+            | LexerTokenKind::LoopKeyword
+            | LexerTokenKind::BreakKeyword
+            | LexerTokenKind::ContinueKeyword => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_scope_end(&self) -> bool {
+        match self {
+            // case arms close the body of the previous arm
+            LexerTokenKind::EndKeyword
+            | LexerTokenKind::ElseKeyword
+            | LexerTokenKind::WhenKeyword
+            | LexerTokenKind::OtherwiseKeyword => true,
+            _ => false,
+        }
+    }
 }
 
 impl fmt::Display for LexerTokenKind {
@@ -103,7 +219,26 @@ fn lex_identifier_or_keyword(value: String) -> LexerTokenKind {
         "loop" => LexerTokenKind::LoopKeyword,
         "from" => LexerTokenKind::FromKeyword,
         "to" => LexerTokenKind::ToKeyword,
+        "step" => LexerTokenKind::StepKeyword,
         "while" => LexerTokenKind::WhileKeyword,
+        "break" => LexerTokenKind::BreakKeyword,
+        "continue" => LexerTokenKind::ContinueKeyword,
+        "div" => LexerTokenKind::DivKeyword,
+        "mod" => LexerTokenKind::ModKeyword,
+        "and" => LexerTokenKind::AndKeyword,
+        "or" => LexerTokenKind::OrKeyword,
+        "not" => LexerTokenKind::NotKeyword,
+        "class" => LexerTokenKind::ClassKeyword,
+        "constructor" => LexerTokenKind::ConstructorKeyword,
+        "extends" => LexerTokenKind::ExtendsKeyword,
+        "private" => LexerTokenKind::PrivateKeyword,
+        "constant" => LexerTokenKind::ConstantKeyword,
+        "import" => LexerTokenKind::ImportKeyword,
+        "case" => LexerTokenKind::CaseKeyword,
+        "of" => LexerTokenKind::OfKeyword,
+        "when" => LexerTokenKind::WhenKeyword,
+        "otherwise" => LexerTokenKind::OtherwiseKeyword,
+        "ref" => LexerTokenKind::RefKeyword,
         _ => LexerTokenKind::IdentifierToken(value),
     }
 }
@@ -111,16 +246,39 @@ fn lex_identifier_or_keyword(value: String) -> LexerTokenKind {
 fn lex_rolling(
     iter: &mut Peekable<Chars>,
     current: char,
+    start_loc: Location,
     column: &mut usize,
     char_offset: &mut usize,
+    errors: &mut ErrorBag,
 ) -> LexerTokenKind {
     let mut value = current.to_string();
     let is_numeric = current.is_numeric();
+    let mut is_real = false;
 
     loop {
         let peek = iter.peek();
         match peek {
             Some(next) => {
+                if is_numeric && !is_real && *next == '.' {
+                    // only a decimal point if a digit follows,
+                    // otherwise leave the dot for the next token
+                    let mut ahead = iter.clone();
+                    ahead.next();
+
+                    match ahead.peek() {
+                        Some(c) if c.is_ascii_digit() => {}
+                        _ => break,
+                    }
+
+                    is_real = true;
+                    *column += 1;
+                    *char_offset += 1;
+
+                    value.push('.');
+                    iter.next();
+                    continue;
+                }
+
                 if !(next.is_alphanumeric() || *next == '_') {
                     break;
                 }
@@ -145,63 +303,302 @@ fn lex_rolling(
         };
     }
 
+    if is_real {
+        let real_value = value.parse::<f64>();
+        match real_value {
+            Ok(v) if v.is_finite() => {
+                return LexerTokenKind::RealLiteralToken(v);
+            }
+            _ => {
+                let end_loc = Location::new(start_loc.line, *column, *char_offset);
+                let span = Span::from_loc(start_loc, end_loc);
+                errors.add(ErrorKind::NumberParsing, span);
+                return LexerTokenKind::RealLiteralToken(0.0);
+            }
+        }
+    }
+
     if is_numeric {
-        let int_value = value.parse();
+        let int_value = value.parse::<i64>();
         match int_value {
             Ok(v) => {
                 return LexerTokenKind::IntegerLiteralToken(v);
             }
-            Err(_) => unreachable!(),
+            Err(e) => {
+                // keep lexing with a placeholder value,
+                // the error stops the program from running
+                let end_loc = Location::new(start_loc.line, *column, *char_offset);
+                let span = Span::from_loc(start_loc, end_loc);
+
+                let error_kind = match e.kind() {
+                    IntErrorKind::PosOverflow => ErrorKind::IntegerOverflow(value),
+                    _ => ErrorKind::NumberParsing,
+                };
+
+                errors.add(error_kind, span);
+                return LexerTokenKind::IntegerLiteralToken(0);
+            }
         }
     }
 
     lex_identifier_or_keyword(value)
 }
 
+fn lex_escape(
+    iter: &mut Peekable<Chars>,
+    line: usize,
+    column: &mut usize,
+    char_offset: &mut usize,
+    errors: &mut ErrorBag,
+) -> Option<char> {
+    // current value is \
+    let start_loc = Location::new(line, *column, *char_offset);
+    iter.next();
+    *column += 1;
+    *char_offset += 1;
+
+    let mut escape = "\\".to_string();
+    let escaped = match iter.peek() {
+        Some(c) if *c != '\n' => {
+            let c = *c;
+            escape.push(c);
+            iter.next();
+            *column += 1;
+            *char_offset += 1;
+
+            match c {
+                '"' => Some('"'),
+                '\\' => Some('\\'),
+                'n' => Some('\n'),
+                't' => Some('\t'),
+                'r' => Some('\r'),
+                '{' => Some('{'),
+                '}' => Some('}'),
+                'u' => {
+                    // unicode escape in the form \u{1F600}
+                    let mut hex = String::new();
+                    let mut closed = false;
+
+                    if iter.peek() == Some(&'{') {
+                        escape.push('{');
+                        iter.next();
+                        *column += 1;
+                        *char_offset += 1;
+
+                        loop {
+                            let c = match iter.peek() {
+                                Some(c) if c.is_ascii_hexdigit() && hex.len() < 6 => *c,
+                                Some('}') => {
+                                    closed = true;
+                                    '}'
+                                }
+                                _ => break,
+                            };
+
+                            escape.push(c);
+                            iter.next();
+                            *column += 1;
+                            *char_offset += 1;
+
+                            if closed {
+                                break;
+                            }
+
+                            hex.push(c);
+                        }
+                    }
+
+                    match u32::from_str_radix(&hex, 16) {
+                        Ok(code) if closed => char::from_u32(code),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+
+    if escaped.is_none() {
+        let end_loc = Location::new(line, *column, *char_offset);
+        let span = Span::from_loc(start_loc, end_loc);
+        errors.add(ErrorKind::InvalidEscapeSequence(escape), span);
+    }
+
+    escaped
+}
+
 fn lex_string(
     iter: &mut Peekable<Chars>,
+    start_loc: Location,
+    continued: bool,
     column: &mut usize,
     char_offset: &mut usize,
+    errors: &mut ErrorBag,
 ) -> LexerTokenKind {
-    // current value is "
+    // current value is " or the } closing
+    // an interpolated expression
     let mut value = String::new();
+    let mut interpolates = false;
 
     loop {
         let peek = iter.peek();
         match peek {
             Some(next) => {
-                if *next == '\"' {
+                if *next == '\"' || *next == '{' {
+                    // closing quote or opening brace
+                    // is part of the token
+                    interpolates = *next == '{';
+                    *column += 1;
+                    *char_offset += 1;
                     iter.next();
                     break;
                 }
 
-                // TODO: Handle line breaks
+                if *next == '\n' {
+                    // strings can't span multiple lines, leave
+                    // the line break for the main loop
+                    let end_loc = Location::new(start_loc.line, *column, *char_offset);
+                    let span = Span::from_loc(start_loc, end_loc);
+                    errors.add(ErrorKind::UnterminatedString, span);
+                    break;
+                }
+
+                if *next == '\\' {
+                    // bad escapes are reported and left out
+                    let line = start_loc.line;
+                    if let Some(c) = lex_escape(iter, line, column, char_offset, errors) {
+                        value.push(c);
+                    }
+
+                    continue;
+                }
+
+                *column += 1;
+                *char_offset += 1;
+
+                value.push(*next);
+                iter.next();
+            }
+            None => {
+                let end_loc = Location::new(start_loc.line, *column, *char_offset);
+                let span = Span::from_loc(start_loc, end_loc);
+                errors.add(ErrorKind::UnterminatedString, span);
+                break;
+            }
+        }
+    }
+
+    match (continued, interpolates) {
+        (false, false) => LexerTokenKind::StringLiteralToken(value),
+        (false, true) => LexerTokenKind::InterpolationStartToken(value),
+        (true, true) => LexerTokenKind::InterpolationMiddleToken(value),
+        (true, false) => LexerTokenKind::InterpolationEndToken(value),
+    }
+}
 
+fn lex_line_comment(
+    iter: &mut Peekable<Chars>,
+    column: &mut usize,
+    char_offset: &mut usize,
+) -> String {
+    // current value is #
+    let mut value = String::new();
+
+    loop {
+        match iter.peek() {
+            Some('\n') | None => break,
+            Some(next) => {
                 *column += 1;
                 *char_offset += 1;
 
                 value.push(*next);
                 iter.next();
             }
+        }
+    }
+
+    value
+}
+
+fn lex_block_comment(
+    iter: &mut Peekable<Chars>,
+    start_loc: Location,
+    line: &mut usize,
+    column: &mut usize,
+    char_offset: &mut usize,
+    errors: &mut ErrorBag,
+) -> String {
+    // current value is /, next is *
+    iter.next();
+    *column += 1;
+    *char_offset += 1;
+
+    let mut value = String::new();
+
+    loop {
+        let current = match iter.next() {
+            Some(c) => c,
             None => {
-                // TODO: report error, unclosed string
+                let end_loc = Location::new(*line, *column, *char_offset);
+                let span = Span::from_loc(start_loc, end_loc);
+                errors.add(ErrorKind::UnterminatedComment, span);
                 break;
             }
+        };
+
+        *column += 1;
+        *char_offset += 1;
+
+        if current == '\n' {
+            *line += 1;
+            *column = 0;
+        }
+
+        if current == '*' && iter.peek() == Some(&'/') {
+            iter.next();
+            *column += 1;
+            *char_offset += 1;
+            break;
+        }
+
+        value.push(current);
+    }
+
+    value
+}
+
+fn add_trivia(tokens: &mut Vec<LexerToken>, pending: &mut Vec<Trivia>, trivia: Trivia) {
+    // a comment on the same line as the previous token
+    // trails it, anything else leads the next token
+    if pending.is_empty() {
+        if let Some(prev) = tokens.last_mut() {
+            if prev.span.end.line == trivia.span.start.line {
+                prev.trailing_trivia.push(trivia);
+                return;
+            }
         }
     }
 
-    let kind = LexerTokenKind::StringLiteralToken(value);
-    return kind;
+    pending.push(trivia);
 }
 
-pub fn lex(content: String) -> Vec<LexerToken> {
+// returns the tokens and the comments after the last token,
+// which is every comment of a file without tokens
+pub fn lex(content: String, file: FileId, errors: &mut ErrorBag) -> (Vec<LexerToken>, Vec<Trivia>) {
+    let num_errors = errors.errors.len();
     let mut tokens: Vec<LexerToken> = vec![];
+    let mut pending_trivia: Vec<Trivia> = vec![];
     let mut chars = content.chars().peekable();
 
     let mut line: usize = 0;
     let mut column: usize = 0;
     let mut char_offset: usize = 0;
 
+    // number of interpolated expressions
+    // the lexer is currently inside of
+    let mut interpolation_depth: usize = 0;
+
     loop {
         let current = match chars.next() {
             Some(c) => c,
@@ -220,6 +617,8 @@ pub fn lex(content: String) -> Vec<LexerToken> {
                     Some(n) => match n {
                         '>' => {
                             chars.next();
+                            column += 1;
+                            char_offset += 1;
                             LexerTokenKind::ArrowToken
                         }
                         _ => LexerTokenKind::MinusToken,
@@ -228,14 +627,45 @@ pub fn lex(content: String) -> Vec<LexerToken> {
                 }
             }
             '*' => LexerTokenKind::StarToken,
-            '/' => LexerTokenKind::SlashToken,
-            '!' => LexerTokenKind::BangToken,
+            '/' => match chars.peek() {
+                Some('*') => {
+                    let value = lex_block_comment(
+                        &mut chars,
+                        start_loc,
+                        &mut line,
+                        &mut column,
+                        &mut char_offset,
+                        errors,
+                    );
+
+                    let end_loc = Location::new(line, column, char_offset);
+                    let trivia = Trivia {
+                        kind: TriviaKind::BlockComment(value),
+                        span: Span::from_loc(start_loc, end_loc),
+                    };
+
+                    add_trivia(&mut tokens, &mut pending_trivia, trivia);
+                    continue;
+                }
+                _ => LexerTokenKind::SlashToken,
+            },
+            '!' => match chars.peek() {
+                Some('=') => {
+                    chars.next();
+                    column += 1;
+                    char_offset += 1;
+                    LexerTokenKind::BangEqualsToken
+                }
+                _ => LexerTokenKind::BangToken,
+            },
             '=' => {
                 let next_peek = chars.peek();
                 match next_peek {
                     Some(n) => match n {
                         '=' => {
                             chars.next();
+                            column += 1;
+                            char_offset += 1;
                             LexerTokenKind::EqualsEqualsToken
                         }
                         _ => LexerTokenKind::EqualsToken,
@@ -243,30 +673,121 @@ pub fn lex(content: String) -> Vec<LexerToken> {
                     None => LexerTokenKind::EqualsToken,
                 }
             }
-            '>' => LexerTokenKind::GreaterThanToken,
-            '<' => LexerTokenKind::LesserThanToken,
+            '>' => match chars.peek() {
+                Some('=') => {
+                    chars.next();
+                    column += 1;
+                    char_offset += 1;
+                    LexerTokenKind::GreaterThanEqualsToken
+                }
+                _ => LexerTokenKind::GreaterThanToken,
+            },
+            '<' => match chars.peek() {
+                Some('=') => {
+                    chars.next();
+                    column += 1;
+                    char_offset += 1;
+                    LexerTokenKind::LesserThanEqualsToken
+                }
+                _ => LexerTokenKind::LesserThanToken,
+            },
+            // glyphs used in printed IB papers
+            '≠' => LexerTokenKind::BangEqualsToken,
+            '≥' => LexerTokenKind::GreaterThanEqualsToken,
+            '≤' => LexerTokenKind::LesserThanEqualsToken,
             '(' => LexerTokenKind::OpenParenthesisToken,
             ')' => LexerTokenKind::CloseParenthesisToken,
+            '[' => LexerTokenKind::OpenBracketToken,
+            ']' => LexerTokenKind::CloseBracketToken,
             ',' => LexerTokenKind::CommaToken,
             ':' => LexerTokenKind::ColonToken,
             '.' => LexerTokenKind::DotToken,
+            '#' => {
+                let value = lex_line_comment(&mut chars, &mut column, &mut char_offset);
+
+                let end_loc = Location::new(line, column, char_offset);
+                let trivia = Trivia {
+                    kind: TriviaKind::LineComment(value),
+                    span: Span::from_loc(start_loc, end_loc),
+                };
+
+                add_trivia(&mut tokens, &mut pending_trivia, trivia);
+                continue;
+            }
             ' ' => continue,
             '\n' => {
+                // an unclosed interpolation can't continue on
+                // the next line, the parser reports the error
+                interpolation_depth = 0;
                 line += 1;
                 column = 0;
                 continue;
             }
-            '\r' => continue,
-            '"' => lex_string(&mut chars, &mut column, &mut char_offset),
-            _ => lex_rolling(&mut chars, current, &mut column, &mut char_offset),
+            '\r' | '\t' => continue,
+            '"' | '}' if current == '"' || interpolation_depth > 0 => {
+                let continued = current == '}';
+                let kind = lex_string(
+                    &mut chars,
+                    start_loc,
+                    continued,
+                    &mut column,
+                    &mut char_offset,
+                    errors,
+                );
+
+                match kind {
+                    LexerTokenKind::InterpolationStartToken(_) => interpolation_depth += 1,
+                    LexerTokenKind::InterpolationEndToken(_) => interpolation_depth -= 1,
+                    _ => {}
+                }
+
+                kind
+            }
+            c if c.is_alphanumeric() || c == '_' => lex_rolling(
+                &mut chars,
+                current,
+                start_loc,
+                &mut column,
+                &mut char_offset,
+                errors,
+            ),
+            c if c.is_whitespace() => continue,
+            c => {
+                // skip the character, the following
+                // tokens are still lexed as usual
+                let end_loc = Location::new(line, column, char_offset);
+                let span = Span::from_loc(start_loc, end_loc);
+                errors.add(ErrorKind::UnexpectedCharacter(c), span);
+                continue;
+            }
         };
 
         let end_loc = Location::new(line, column, char_offset);
         let span = Span::from_loc(start_loc, end_loc);
 
-        let token = LexerToken::new(kind, span);
+        let mut token = LexerToken::new(kind, span);
+        token.leading_trivia = std::mem::take(&mut pending_trivia);
         tokens.push(token);
     }
 
-    tokens
+    // every span is attributed to the lexed file,
+    // the parser builds its spans from these
+    for token in tokens.iter_mut() {
+        token.span.set_file(file);
+
+        let trivia = token.leading_trivia.iter_mut();
+        for t in trivia.chain(token.trailing_trivia.iter_mut()) {
+            t.span.set_file(file);
+        }
+    }
+
+    for t in pending_trivia.iter_mut() {
+        t.span.set_file(file);
+    }
+
+    for error in errors.errors[num_errors..].iter_mut() {
+        error.span.set_file(file);
+    }
+
+    (tokens, pending_trivia)
 }
@@ -4,9 +4,9 @@ pub mod syntax_token;
 
 use self::syntax_token::SyntaxToken;
 
-use super::error_bag::ErrorBag;
+use super::{error_bag::ErrorBag, span::FileId};
 
-pub fn parse(content: String, errors: &mut ErrorBag) -> Option<SyntaxToken> {
-    let tokens = lexer::lex(content);
-    parser::parse(tokens, errors)
+pub fn parse(content: String, file: FileId, errors: &mut ErrorBag) -> SyntaxToken {
+    let (tokens, trailing_trivia) = lexer::lex(content, file, errors);
+    parser::parse(tokens, trailing_trivia, file, errors)
 }
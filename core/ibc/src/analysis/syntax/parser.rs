@@ -1,25 +1,78 @@
-use std::{iter::Peekable, slice::Iter};
+use std::{iter, iter::Peekable};
 
 use crate::analysis::{
     error_bag::{ErrorBag, ErrorKind},
     operator::Operator,
-    span::{Location, Span},
+    span::{FileId, Location, Span},
 };
 
 use super::{
-    lexer::{LexerToken, LexerTokenKind},
-    syntax_token::{SyntaxKind, SyntaxToken},
+    lexer::{LexerToken, LexerTokenKind, Trivia},
+    syntax_token::{SyntaxKind, SyntaxToken, TypeSyntax},
 };
 
-type LexerTokens<'a> = Peekable<Iter<'a, LexerToken>>;
+type LexerTokens<'a> = Peekable<Box<dyn Iterator<Item = &'a LexerToken> + 'a>>;
+
+fn lexer_tokens<'a>(tokens: impl Iterator<Item = &'a LexerToken> + 'a) -> LexerTokens<'a> {
+    let tokens: Box<dyn Iterator<Item = &'a LexerToken> + 'a> = Box::new(tokens);
+    tokens.peekable()
+}
 
 struct Parser<'a> {
     tokens: LexerTokens<'a>,
+    // every `>=` token split into `>` and `=`,
+    // used when it closes a generic list
+    splits: &'a [(LexerToken, LexerToken)],
+    position: usize,
+    prev_span: Option<Span>,
+    file: FileId,
 }
 
 impl<'a> Parser<'a> {
-    fn new(tokens: LexerTokens<'a>) -> Self {
-        Parser { tokens: tokens }
+    fn new(tokens: LexerTokens<'a>, splits: &'a [(LexerToken, LexerToken)], file: FileId) -> Self {
+        Parser {
+            tokens: tokens,
+            splits: splits,
+            position: 0,
+            prev_span: None,
+            file: file,
+        }
+    }
+
+    fn file_start(&self) -> Location {
+        let mut loc = Location::new(0, 0, 0);
+        loc.file = self.file;
+        loc
+    }
+
+    fn next_token(&mut self) -> Option<&'a LexerToken> {
+        let next = self.tokens.next();
+        if let Some(t) = next {
+            self.position += 1;
+            self.prev_span = Some(t.span.clone());
+        }
+
+        next
+    }
+
+    fn split_greater_than_equals(&mut self) {
+        // the `>` of the split token is parsed next,
+        // followed by the `=`
+        let Some(token) = self.tokens.next() else {
+            return;
+        };
+
+        let split = self
+            .splits
+            .iter()
+            .find(|(greater, _)| greater.span.start.char_offset == token.span.start.char_offset);
+
+        let Some((greater, equals)) = split else {
+            unreachable!()
+        };
+
+        let rest = std::mem::replace(&mut self.tokens, lexer_tokens(iter::empty()));
+        self.tokens = lexer_tokens([greater, equals].into_iter().chain(rest));
     }
 
     fn parse_expression(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
@@ -38,7 +91,7 @@ impl<'a> Parser<'a> {
 
         let mut lhs = if unary_precedence != 0 && unary_precedence >= parent_precedence {
             // unary expression
-            let next = self.tokens.next().unwrap();
+            let next = self.next_token().unwrap();
             let next_span = next.span.clone();
 
             let operator = match self.parse_operator(next) {
@@ -80,7 +133,7 @@ impl<'a> Parser<'a> {
                 break;
             }
 
-            let operator_token = match self.tokens.next() {
+            let operator_token = match self.next_token() {
                 Some(t) => t,
                 None => {
                     let error_kind = ErrorKind::ExpectedToken("binary operator".to_string());
@@ -98,11 +151,17 @@ impl<'a> Parser<'a> {
                 }
             };
 
+            let num_errors = errors.errors.len();
             let rhs = match self.parse_binary_expression(precedence, errors) {
                 Some(r) => r,
                 None => {
-                    let error_kind = ErrorKind::ExpectedToken("expression".to_string());
-                    errors.add(error_kind, operator_token.span);
+                    // don't report twice if the rhs
+                    // already reported its own error
+                    if errors.errors.len() == num_errors {
+                        let error_kind = ErrorKind::ExpectedToken("expression".to_string());
+                        errors.add(error_kind, self.expected_span());
+                    }
+
                     return None;
                 }
             };
@@ -136,22 +195,32 @@ impl<'a> Parser<'a> {
                     LexerTokenKind::IdentifierToken(_) => self.parse_reference_based_token(errors),
                     LexerTokenKind::IntegerLiteralToken(val) => {
                         // consume token
-                        self.tokens.next();
+                        self.next_token();
 
                         let kind = SyntaxKind::IntegerLiteralExpression(val.clone());
                         let token = SyntaxToken::new(kind, span);
                         Some(token)
                     }
+                    LexerTokenKind::RealLiteralToken(val) => {
+                        self.next_token();
+
+                        let kind = SyntaxKind::RealLiteralExpression(val.clone());
+                        let token = SyntaxToken::new(kind, span);
+                        Some(token)
+                    }
                     LexerTokenKind::StringLiteralToken(val) => {
-                        self.tokens.next();
+                        self.next_token();
 
                         let kind = SyntaxKind::StringLiteralExpression(val.clone());
                         let token = SyntaxToken::new(kind, span);
                         Some(token)
                     }
+                    LexerTokenKind::InterpolationStartToken(_) => {
+                        self.parse_interpolated_string(errors)
+                    }
                     LexerTokenKind::TrueKeyword => {
                         // consume token
-                        self.tokens.next();
+                        self.next_token();
 
                         let kind = SyntaxKind::BooleanLiteralExpression(true);
                         let token = SyntaxToken::new(kind, span);
@@ -159,23 +228,207 @@ impl<'a> Parser<'a> {
                     }
                     LexerTokenKind::FalseKeyword => {
                         // consume token
-                        self.tokens.next();
+                        self.next_token();
 
                         let kind = SyntaxKind::BooleanLiteralExpression(false);
                         let token = SyntaxToken::new(kind, span);
                         Some(token)
                     }
                     LexerTokenKind::NewKeyword => self.parse_instantiation_expression(errors),
+                    LexerTokenKind::OpenBracketToken => self.parse_array_literal(errors),
                     _ => return None,
                 }
             }
             None => {
-                // TODO: Add location, use some sort of last parsed token location
                 let error_kind = ErrorKind::ExpectedPrimaryExpression;
-                errors.add(error_kind, Span::new(0, 0, 0, 0, 0, 0));
+                errors.add(error_kind, self.expected_span());
+                return None;
+            }
+        }
+    }
+
+    fn parse_index(&mut self, base: SyntaxToken, errors: &mut ErrorBag) -> Option<SyntaxToken> {
+        // consume [
+        self.next_token();
+
+        let num_errors = errors.errors.len();
+        let index = match self.parse_expression(errors) {
+            Some(i) => i,
+            None => {
+                if errors.errors.len() == num_errors {
+                    let error_kind = ErrorKind::ExpectedToken("index expression".to_string());
+                    errors.add(error_kind, self.expected_span());
+                }
+
+                return None;
+            }
+        };
+
+        if !self.expect_next_token(LexerTokenKind::CloseBracketToken) {
+            let error_kind = ErrorKind::ExpectedToken("close bracket ']'".to_string());
+            errors.add(error_kind, self.expected_span());
+            return None;
+        }
+
+        let end_loc = self.prev_span.unwrap().end;
+        let span = Span::from_loc(base.span.start, end_loc);
+        let kind = SyntaxKind::IndexExpression {
+            base: Box::new(base),
+            index: Box::new(index),
+        };
+
+        let token = SyntaxToken::new(kind, span);
+        Some(token)
+    }
+
+    fn parse_index_assignment(
+        &mut self,
+        target: SyntaxToken,
+        errors: &mut ErrorBag,
+    ) -> Option<SyntaxToken> {
+        // consume =
+        self.next_token();
+
+        let num_errors = errors.errors.len();
+        let value = match self.parse_expression(errors) {
+            Some(e) => e,
+            None => {
+                if errors.errors.len() == num_errors {
+                    let error_kind = ErrorKind::ExpectedToken("expression".to_string());
+                    errors.add(error_kind, self.expected_span());
+                }
+
+                return None;
+            }
+        };
+
+        let SyntaxKind::IndexExpression { base, index } = target.kind else {
+            unreachable!()
+        };
+
+        let span = Span::from_loc(target.span.start, value.span.end);
+        let kind = SyntaxKind::IndexAssignmentExpression {
+            base: base,
+            index: index,
+            value: Box::new(value),
+        };
+
+        let token = SyntaxToken::new(kind, span);
+        Some(token)
+    }
+
+    fn parse_array_literal(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
+        let open_bracket = self.next_token().unwrap();
+        let mut elements: Vec<SyntaxToken> = Vec::new();
+
+        loop {
+            if self.expect_next_token(LexerTokenKind::CloseBracketToken) {
+                break;
+            }
+
+            let num_errors = errors.errors.len();
+            let element = match self.parse_expression(errors) {
+                Some(e) => e,
+                None => {
+                    if errors.errors.len() == num_errors {
+                        let error_kind = ErrorKind::ExpectedToken("array element".to_string());
+                        errors.add(error_kind, self.expected_span());
+                    }
+
+                    return None;
+                }
+            };
+
+            elements.push(element);
+
+            // either another element or the end of the array
+            if self.expect_next_token(LexerTokenKind::CommaToken) {
+                continue;
+            }
+
+            if !self.expect_next_token(LexerTokenKind::CloseBracketToken) {
+                let error_kind = ErrorKind::ExpectedToken("comma or close bracket ']'".to_string());
+                errors.add(error_kind, self.expected_span());
                 return None;
             }
+
+            break;
+        }
+
+        let end_loc = self.prev_span.unwrap().end;
+        let span = Span::from_loc(open_bracket.span.start, end_loc);
+        let kind = SyntaxKind::ArrayLiteralExpression { elements: elements };
+        let token = SyntaxToken::new(kind, span);
+        Some(token)
+    }
+
+    fn parse_interpolated_string(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
+        let start = self.next_token().unwrap();
+        let mut parts: Vec<SyntaxToken> = Vec::new();
+
+        if let LexerTokenKind::InterpolationStartToken(text) = &start.kind {
+            if !text.is_empty() {
+                let kind = SyntaxKind::StringLiteralExpression(text.clone());
+                parts.push(SyntaxToken::new(kind, start.span));
+            }
+        }
+
+        loop {
+            let num_errors = errors.errors.len();
+            let expr = match self.parse_expression(errors) {
+                Some(e) => e,
+                None => {
+                    if errors.errors.len() == num_errors {
+                        let error_kind = ErrorKind::ExpectedToken("expression".to_string());
+                        errors.add(error_kind, self.expected_span());
+                    }
+
+                    return None;
+                }
+            };
+
+            parts.push(expr);
+
+            // the text after the expression
+            let (text, span, is_end) = match self.tokens.peek() {
+                Some(LexerToken {
+                    kind: LexerTokenKind::InterpolationMiddleToken(text),
+                    span,
+                    ..
+                }) => (text.clone(), *span, false),
+                Some(LexerToken {
+                    kind: LexerTokenKind::InterpolationEndToken(text),
+                    span,
+                    ..
+                }) => (text.clone(), *span, true),
+                _ => {
+                    let error_kind = ErrorKind::ExpectedToken("close brace '}'".to_string());
+                    errors.add(error_kind, self.expected_span());
+                    return None;
+                }
+            };
+
+            self.next_token();
+
+            if !text.is_empty() {
+                let kind = SyntaxKind::StringLiteralExpression(text);
+                parts.push(SyntaxToken::new(kind, span));
+            }
+
+            if is_end {
+                break;
+            }
         }
+
+        let end_loc = match self.prev_span {
+            Some(s) => s.end,
+            None => start.span.end,
+        };
+
+        let span = Span::from_loc(start.span.start, end_loc);
+        let kind = SyntaxKind::InterpolatedStringExpression { parts: parts };
+        let token = SyntaxToken::new(kind, span);
+        Some(token)
     }
 
     fn parse_reference_based_token(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
@@ -183,15 +436,29 @@ impl<'a> Parser<'a> {
         let (identifier, identifier_span) = match self.parse_identifier() {
             Some(i) => i,
             None => {
-                // TODO: Agains use some sort of last token loc
                 let error_kind = ErrorKind::ExpectedToken("identifier".to_string());
-                errors.add(error_kind, Span::new(0, 0, 0, 0, 0, 0));
+                errors.add(error_kind, self.expected_span());
                 return None;
             }
         };
 
         let reference_kind = SyntaxKind::ReferenceExpression(identifier.clone());
-        let reference = SyntaxToken::new(reference_kind, identifier_span);
+        let mut reference = SyntaxToken::new(reference_kind, identifier_span);
+
+        // index expressions, i.e. arr[i][j]
+        let mut is_indexed = false;
+        while let Some(LexerToken {
+            kind: LexerTokenKind::OpenBracketToken,
+            ..
+        }) = self.tokens.peek()
+        {
+            reference = match self.parse_index(reference, errors) {
+                Some(r) => r,
+                None => return None,
+            };
+
+            is_indexed = true;
+        }
 
         let peek = match self.tokens.peek() {
             Some(p) => p,
@@ -199,7 +466,10 @@ impl<'a> Parser<'a> {
         };
 
         match peek.kind {
-            LexerTokenKind::OpenParenthesisToken => {
+            LexerTokenKind::EqualsToken if is_indexed => {
+                self.parse_index_assignment(reference, errors)
+            }
+            LexerTokenKind::OpenParenthesisToken if !is_indexed => {
                 // call expression
                 let arguments = match self.parse_argument_list(errors) {
                     Some(a) => a,
@@ -220,14 +490,21 @@ impl<'a> Parser<'a> {
                 let token = SyntaxToken::new(kind, span);
                 Some(token)
             }
+            LexerTokenKind::ColonToken if !is_indexed => {
+                self.parse_variable_declaration(identifier, identifier_span, errors)
+            }
             LexerTokenKind::EqualsToken => {
                 // assignment expression
-                let equals = self.tokens.next().unwrap();
+                self.next_token();
+                let num_errors = errors.errors.len();
                 let value = match self.parse_expression(errors) {
                     Some(e) => e,
                     None => {
-                        let error_kind = ErrorKind::ExpectedToken("expression".to_string());
-                        errors.add(error_kind, equals.span);
+                        if errors.errors.len() == num_errors {
+                            let error_kind = ErrorKind::ExpectedToken("expression".to_string());
+                            errors.add(error_kind, self.expected_span());
+                        }
+
                         return None;
                     }
                 };
@@ -244,12 +521,25 @@ impl<'a> Parser<'a> {
             }
             LexerTokenKind::DotToken => {
                 // object call expression
-                let dot = self.tokens.next().unwrap();
+                self.next_token();
+
+                match self.tokens.peek() {
+                    Some(LexerToken {
+                        kind: LexerTokenKind::IdentifierToken(_),
+                        ..
+                    }) => {}
+                    _ => {
+                        let error_kind = ErrorKind::ExpectedToken("object member".to_string());
+                        errors.add(error_kind, self.expected_span());
+                        return None;
+                    }
+                }
+
                 let next_expr = match self.parse_reference_based_token(errors) {
                     Some(n) => n,
                     None => {
                         let error_kind = ErrorKind::ExpectedToken("object member".to_string());
-                        errors.add(error_kind, dot.span);
+                        errors.add(error_kind, self.expected_span());
                         return None;
                     }
                 };
@@ -267,64 +557,121 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_instantiation_expression(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
-        let new_keyword = self.tokens.next().unwrap();
+    fn parse_variable_declaration(
+        &mut self,
+        identifier: String,
+        identifier_span: Span,
+        errors: &mut ErrorBag,
+    ) -> Option<SyntaxToken> {
+        // variable declaration, i.e. x: Int = 5
+        if !self.expect_next_token(LexerTokenKind::ColonToken) {
+            let error_kind = ErrorKind::ExpectedToken("type annotation".to_string());
+            errors.add(error_kind, self.expected_span());
+            return None;
+        }
+
+        let type_annotation = match self.parse_type("type annotation", errors) {
+            Some(t) => t,
+            None => return None,
+        };
+
+        let mut end_loc = type_annotation.span.end;
+        let mut value = None;
+        if self.expect_next_token_peek(LexerTokenKind::EqualsToken) {
+            self.next_token();
+            let num_errors = errors.errors.len();
+            let expr = match self.parse_expression(errors) {
+                Some(e) => e,
+                None => {
+                    if errors.errors.len() == num_errors {
+                        let error_kind = ErrorKind::ExpectedToken("expression".to_string());
+                        errors.add(error_kind, self.expected_span());
+                    }
+
+                    return None;
+                }
+            };
+
+            end_loc = expr.span.end;
+            value = Some(Box::new(expr));
+        }
 
+        let kind = SyntaxKind::VariableDeclaration {
+            identifier: identifier,
+            type_annotation: type_annotation,
+            value: value,
+        };
+
+        let span = Span::from_loc(identifier_span.start, end_loc);
+        let token = SyntaxToken::new(kind, span);
+        Some(token)
+    }
+
+    fn parse_type(&mut self, expected: &str, errors: &mut ErrorBag) -> Option<TypeSyntax> {
         let (identifier, identifier_span) = match self.parse_identifier() {
-            Some((i, s)) => (i, s),
+            Some(i) => i,
             None => {
-                let error_kind = ErrorKind::ExpectedToken("Identifier".to_string());
-                errors.add(error_kind, new_keyword.span);
+                let error_kind = ErrorKind::ExpectedToken(expected.to_string());
+                errors.add(error_kind, self.expected_span());
                 return None;
             }
         };
 
-        let peek = match self.tokens.peek() {
-            Some(i) => i,
-            None => {
-                let error_kind = ErrorKind::ExpectedToken("Argument List".to_string());
-                errors.add(error_kind, identifier_span);
+        let mut end_loc = identifier_span.end;
+        let mut generic = None;
+
+        // generics can be nested, i.e. Array<Stack<Int>>
+        if self.expect_next_token_peek(LexerTokenKind::LesserThanToken) {
+            self.next_token();
+            let generic_type = match self.parse_type("Type Identifier", errors) {
+                Some(t) => t,
+                None => return None,
+            };
+
+            // consume ge token
+            if self.expect_next_token_peek(LexerTokenKind::GreaterThanEqualsToken) {
+                self.split_greater_than_equals();
+            }
+
+            if !self.expect_next_token(LexerTokenKind::GreaterThanToken) {
+                let error_kind = ErrorKind::ExpectedToken("greater than '>'".to_string());
+                errors.add(error_kind, self.expected_span());
                 return None;
             }
+
+            end_loc = self.prev_span.unwrap().end;
+            generic = Some(Box::new(generic_type));
+        }
+
+        let type_syntax = TypeSyntax {
+            identifier: identifier,
+            generic: generic,
+            span: Span::from_loc(identifier_span.start, end_loc),
         };
 
-        let type_param = match peek.kind {
-            LexerTokenKind::LesserThanToken => {
-                let le_token = self.tokens.next().unwrap();
-                let (identifier, _) = match self.parse_identifier() {
-                    Some(i) => i,
-                    None => {
-                        let error_kind = ErrorKind::ExpectedToken("Type Identifier".to_string());
-                        errors.add(error_kind, le_token.span);
-                        return None;
-                    }
-                };
+        Some(type_syntax)
+    }
 
-                // consume ge token
-                self.expect_next_token(LexerTokenKind::GreaterThanToken);
+    fn parse_instantiation_expression(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
+        let new_keyword = self.next_token().unwrap();
 
-                Some(identifier)
-            }
-            _ => None,
+        let type_syntax = match self.parse_type("Identifier", errors) {
+            Some(t) => t,
+            None => return None,
         };
 
         let arg_list = match self.parse_argument_list(errors) {
             Some(a) => a,
-            None => {
-                let error_kind = ErrorKind::ExpectedToken("Argument List".to_string());
-                errors.add(error_kind, identifier_span);
-                return None;
-            }
+            None => return None,
         };
 
         let end_loc = match arg_list.last() {
             Some(l) => l.span.end,
-            None => identifier_span.end,
+            None => type_syntax.span.end,
         };
 
         let kind = SyntaxKind::InstantiationExpression {
-            type_name: identifier,
-            type_param: type_param,
+            type_syntax: type_syntax,
             args: arg_list,
         };
 
@@ -334,7 +681,13 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_argument_list(&mut self, errors: &mut ErrorBag) -> Option<Vec<SyntaxToken>> {
-        let _open_paren = self.tokens.next().unwrap();
+        if !self.expect_next_token(LexerTokenKind::OpenParenthesisToken) {
+            // argument lists always follow an identifier
+            // or a type parameter, so there is a prev token
+            let error_kind = ErrorKind::ExpectedToken("Argument List".to_string());
+            errors.add(error_kind, self.expected_span());
+            return None;
+        }
 
         let mut args: Vec<SyntaxToken> = Vec::new();
         let mut prev_comma = false;
@@ -342,16 +695,20 @@ impl<'a> Parser<'a> {
         loop {
             let peek = match self.tokens.peek() {
                 Some(p) => p,
-                None => return None,
+                None => {
+                    let error_kind = ErrorKind::ExpectedToken("close parenthesis ')'".to_string());
+                    errors.add(error_kind, self.expected_span());
+                    return None;
+                }
             };
 
             if let LexerTokenKind::CloseParenthesisToken = peek.kind {
-                let peek_span = peek.span.clone();
-                self.tokens.next();
+                let expected_span = self.expected_span();
+                self.next_token();
 
                 if prev_comma {
                     let error_kind = ErrorKind::ExpectedArgument;
-                    errors.add(error_kind, peek_span);
+                    errors.add(error_kind, expected_span);
                     return None;
                 }
 
@@ -367,7 +724,7 @@ impl<'a> Parser<'a> {
                 Some(p) => p,
                 None => {
                     let error_kind = ErrorKind::ExpectedToken("close parenthesis ')'".to_string());
-                    errors.add(error_kind, expr.span);
+                    errors.add(error_kind, self.expected_span());
                     return None;
                 }
             };
@@ -376,12 +733,12 @@ impl<'a> Parser<'a> {
             match peek.kind {
                 LexerTokenKind::CommaToken => {
                     prev_comma = true;
-                    self.tokens.next();
+                    self.next_token();
                 }
                 LexerTokenKind::CloseParenthesisToken => {}
                 _ => {
                     let error_kind = ErrorKind::ExpectedToken("close parenthesis ')'".to_string());
-                    errors.add(error_kind, peek.span);
+                    errors.add(error_kind, self.expected_span());
                     return None;
                 }
             };
@@ -393,28 +750,25 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_parenthesis_expression(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
-        let left_paren = self.tokens.next().unwrap();
+        let left_paren = self.next_token().unwrap();
         let start_loc = left_paren.span.start;
 
         match self.parse_expression(errors) {
             Some(expr) => {
-                let right_paren = self.tokens.next();
-                match right_paren {
-                    Some(r) => {
-                        let kind = SyntaxKind::ParenthesizedExpression {
-                            inner: Box::new(expr),
-                        };
-
-                        let span = Span::from_loc(start_loc, r.span.end);
-                        let token = SyntaxToken::new(kind, span);
-                        Some(token)
-                    }
-                    None => {
-                        let error_kind = ErrorKind::UnclosedParenthesisExpression;
-                        errors.add(error_kind, expr.span);
-                        None
-                    }
+                if !self.expect_next_token(LexerTokenKind::CloseParenthesisToken) {
+                    let error_kind = ErrorKind::UnclosedParenthesisExpression;
+                    errors.add(error_kind, expr.span);
+                    return None;
                 }
+
+                let end_loc = self.prev_span.unwrap().end;
+                let kind = SyntaxKind::ParenthesizedExpression {
+                    inner: Box::new(expr),
+                };
+
+                let span = Span::from_loc(start_loc, end_loc);
+                let token = SyntaxToken::new(kind, span);
+                Some(token)
             }
             None => None,
         }
@@ -430,13 +784,22 @@ impl<'a> Parser<'a> {
             LexerTokenKind::BangToken => Some(Operator::Not),
             LexerTokenKind::LesserThanToken => Some(Operator::LesserThan),
             LexerTokenKind::GreaterThanToken => Some(Operator::GreaterThan),
+            LexerTokenKind::LesserThanEqualsToken => Some(Operator::LesserThanOrEqual),
+            LexerTokenKind::GreaterThanEqualsToken => Some(Operator::GreaterThanOrEqual),
+            LexerTokenKind::BangEqualsToken => Some(Operator::Inequality),
+            LexerTokenKind::DivKeyword => Some(Operator::IntegerDivision),
+            LexerTokenKind::ModKeyword => Some(Operator::Modulo),
+            LexerTokenKind::AndKeyword => Some(Operator::And),
+            LexerTokenKind::OrKeyword => Some(Operator::Or),
+            LexerTokenKind::NotKeyword => Some(Operator::Not),
             _ => None,
         }
     }
 
     fn parse_output_statement(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
-        let keyword = self.tokens.next().unwrap();
+        let keyword = self.next_token().unwrap();
         let start_loc = keyword.span.start.clone();
+        let num_errors = errors.errors.len();
 
         let expr = self.parse_expression(errors);
         match expr {
@@ -451,70 +814,303 @@ impl<'a> Parser<'a> {
                 Some(token)
             }
             None => {
-                let error_kind = ErrorKind::ExpectedToken("expression".to_string());
-                errors.add(error_kind, keyword.span);
+                if errors.errors.len() == num_errors {
+                    let error_kind = ErrorKind::ExpectedToken("expression".to_string());
+                    errors.add(error_kind, self.expected_span());
+                }
+
                 return None;
             }
         }
     }
 
-    fn parse_if_statement(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
-        let keyword = self.tokens.next().unwrap();
+    fn parse_constant_declaration(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
+        // constant declaration, i.e. constant MAX = 100
+        let keyword = self.next_token().unwrap();
         let start_loc = keyword.span.start.clone();
 
-        let condition = match self.parse_expression(errors) {
-            Some(c) => c,
+        let identifier = match self.parse_identifier() {
+            Some((i, _)) => i,
             None => {
-                let error_kind = ErrorKind::ExpectedToken("condition expression".to_string());
-                errors.add(error_kind, keyword.span);
+                let error_kind = ErrorKind::ExpectedToken("identifier".to_string());
+                errors.add(error_kind, self.expected_span());
                 return None;
             }
         };
 
-        // then keyword
-        if !self.expect_next_token(LexerTokenKind::ThenKeyword) {
-            let error_kind = ErrorKind::ExpectedToken("then keyword".to_string());
-            errors.add(error_kind, condition.span);
+        if !self.expect_next_token(LexerTokenKind::EqualsToken) {
+            let error_kind = ErrorKind::ExpectedToken("equals sign '='".to_string());
+            errors.add(error_kind, self.expected_span());
             return None;
         }
 
-        let body = match self.parse_scope(errors) {
-            Some(b) => b,
+        let num_errors = errors.errors.len();
+        let value = match self.parse_expression(errors) {
+            Some(e) => e,
             None => {
-                let error_kind = ErrorKind::ExpectedScope;
-                errors.add(error_kind, condition.span);
+                if errors.errors.len() == num_errors {
+                    let error_kind = ErrorKind::ExpectedToken("expression".to_string());
+                    errors.add(error_kind, self.expected_span());
+                }
+
                 return None;
             }
         };
 
-        // end keyword
-        if !self.expect_next_token(LexerTokenKind::EndKeyword) {
-            let error_kind = ErrorKind::ExpectedToken("end keyword".to_string());
-            errors.add(error_kind, body.span);
-            return None;
-        }
-
-        let end_loc = body.span.end.clone();
-        let kind = SyntaxKind::IfStatement {
-            condition: Box::new(condition),
-            body: Box::new(body),
+        let span = Span::from_loc(start_loc, value.span.end.clone());
+        let kind = SyntaxKind::ConstantDeclaration {
+            identifier: identifier,
+            value: Box::new(value),
         };
 
-        let span = Span::from_loc(start_loc, end_loc);
         let token = SyntaxToken::new(kind, span);
         Some(token)
     }
 
-    fn parse_return_statement(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
-        let keyword = self.tokens.next().unwrap();
-        let (expr, end_loc) = match self.parse_expression(errors) {
-            Some(e) => {
-                let end_loc = e.span.end.clone();
-                (Some(Box::new(e)), end_loc)
-            }
-            None => (None, keyword.span.end),
-        };
-
+    fn parse_if_statement(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
+        let keyword = self.next_token().unwrap();
+        let start_loc = keyword.span.start.clone();
+        let header_line = keyword.span.start.line;
+
+        // a broken condition still lets us parse the
+        // rest of the statement, so that errors in the
+        // body are reported as well
+        let num_errors = errors.errors.len();
+        let condition = match self.parse_expression(errors) {
+            Some(c) => Some(c),
+            None => {
+                if errors.errors.len() == num_errors {
+                    let error_kind = ErrorKind::ExpectedToken("condition expression".to_string());
+                    errors.add(error_kind, self.expected_span());
+                }
+                self.synchronize_header(header_line, Some(LexerTokenKind::ThenKeyword));
+                None
+            }
+        };
+
+        // then keyword
+        if !self.expect_next_token(LexerTokenKind::ThenKeyword) {
+            if condition.is_some() {
+                let error_kind = ErrorKind::ExpectedToken("then keyword".to_string());
+                errors.add(error_kind, self.expected_span());
+                self.synchronize_header(header_line, Some(LexerTokenKind::ThenKeyword));
+                self.expect_next_token(LexerTokenKind::ThenKeyword);
+            }
+        }
+
+        let body = match self.parse_scope(errors) {
+            Some(b) => b,
+            None => {
+                let error_kind = ErrorKind::ExpectedScope;
+                errors.add(error_kind, keyword.span);
+                return None;
+            }
+        };
+
+        let else_body = if self.expect_next_token_peek(LexerTokenKind::ElseKeyword) {
+            let else_keyword = self.next_token().unwrap();
+            if self.expect_next_token_peek(LexerTokenKind::IfKeyword) {
+                // else if, the chained if statement
+                // consumes the shared end keyword
+                match self.parse_if_statement(errors) {
+                    Some(s) => Some(Box::new(s)),
+                    None => return None,
+                }
+            } else {
+                let else_body = match self.parse_scope(errors) {
+                    Some(b) => b,
+                    None => {
+                        let error_kind = ErrorKind::ExpectedScope;
+                        errors.add(error_kind, else_keyword.span);
+                        return None;
+                    }
+                };
+
+                // a missing end is reported, but
+                // the statement itself is still valid
+                if !self.expect_next_token(LexerTokenKind::EndKeyword) {
+                    let error_kind = ErrorKind::ExpectedToken("end keyword".to_string());
+                    errors.add(error_kind, self.expected_span());
+                }
+
+                Some(Box::new(else_body))
+            }
+        } else {
+            // end keyword
+            if !self.expect_next_token(LexerTokenKind::EndKeyword) {
+                let error_kind = ErrorKind::ExpectedToken("end keyword".to_string());
+                errors.add(error_kind, self.expected_span());
+            }
+
+            None
+        };
+
+        let Some(condition) = condition else {
+            return Some(self.error_node(start_loc));
+        };
+
+        let end_loc = match &else_body {
+            Some(e) => e.span.end.clone(),
+            None => body.span.end.clone(),
+        };
+
+        let kind = SyntaxKind::IfStatement {
+            condition: Box::new(condition),
+            body: Box::new(body),
+            else_body: else_body,
+        };
+
+        let span = Span::from_loc(start_loc, end_loc);
+        let token = SyntaxToken::new(kind, span);
+        Some(token)
+    }
+
+    fn parse_case_statement(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
+        let keyword = self.next_token().unwrap();
+        let start_loc = keyword.span.start.clone();
+        let header_line = keyword.span.start.line;
+
+        // like if statements, a broken header still
+        // lets us parse the arms of the statement
+        let num_errors = errors.errors.len();
+        let expr = match self.parse_expression(errors) {
+            Some(e) => Some(e),
+            None => {
+                if errors.errors.len() == num_errors {
+                    let error_kind = ErrorKind::ExpectedToken("case expression".to_string());
+                    errors.add(error_kind, self.expected_span());
+                }
+                self.synchronize_header(header_line, Some(LexerTokenKind::OfKeyword));
+                None
+            }
+        };
+
+        // of keyword
+        if !self.expect_next_token(LexerTokenKind::OfKeyword) {
+            if expr.is_some() {
+                let error_kind = ErrorKind::ExpectedToken("of keyword".to_string());
+                errors.add(error_kind, self.expected_span());
+                self.synchronize_header(header_line, Some(LexerTokenKind::OfKeyword));
+                self.expect_next_token(LexerTokenKind::OfKeyword);
+            }
+        }
+
+        // statements before the first arm don't
+        // belong to any arm, report and skip them
+        if let Some(p) = self.tokens.peek() {
+            if !p.kind.is_scope_end() {
+                let error_kind = ErrorKind::ExpectedToken("when keyword".to_string());
+                errors.add(error_kind, p.span);
+                self.parse_statement_list(errors);
+            }
+        }
+
+        let mut arms: Vec<SyntaxToken> = Vec::new();
+        while self.expect_next_token_peek(LexerTokenKind::WhenKeyword) {
+            match self.parse_case_arm(errors) {
+                Some(a) => arms.push(a),
+                None => return None,
+            }
+        }
+
+        let otherwise = if self.expect_next_token_peek(LexerTokenKind::OtherwiseKeyword) {
+            let otherwise_keyword = self.next_token().unwrap();
+            match self.parse_scope(errors) {
+                Some(b) => Some(Box::new(b)),
+                None => {
+                    let error_kind = ErrorKind::ExpectedScope;
+                    errors.add(error_kind, otherwise_keyword.span);
+                    return None;
+                }
+            }
+        } else {
+            None
+        };
+
+        if !self.expect_next_token(LexerTokenKind::EndKeyword) {
+            let error_kind = ErrorKind::ExpectedToken("end keyword".to_string());
+            errors.add(error_kind, self.expected_span());
+        }
+
+        let Some(expr) = expr else {
+            return Some(self.error_node(start_loc));
+        };
+
+        let end_loc = self.prev_span.unwrap().end;
+
+        let kind = SyntaxKind::CaseStatement {
+            expr: Box::new(expr),
+            arms: arms,
+            otherwise: otherwise,
+        };
+
+        let span = Span::from_loc(start_loc, end_loc);
+        let token = SyntaxToken::new(kind, span);
+        Some(token)
+    }
+
+    fn parse_case_arm(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
+        let keyword = self.next_token().unwrap();
+        let header_line = keyword.span.start.line;
+
+        // a broken value is replaced with an error
+        // node, the body is still parsed and bound
+        let num_errors = errors.errors.len();
+        let value = match self.parse_expression(errors) {
+            Some(v) => v,
+            None => {
+                if errors.errors.len() == num_errors {
+                    let error_kind = ErrorKind::ExpectedToken("arm value".to_string());
+                    errors.add(error_kind, self.expected_span());
+                }
+                self.synchronize_header(header_line, None);
+                self.error_node(keyword.span.start)
+            }
+        };
+
+        let body = match self.parse_scope(errors) {
+            Some(b) => b,
+            None => {
+                let error_kind = ErrorKind::ExpectedScope;
+                errors.add(error_kind, keyword.span);
+                return None;
+            }
+        };
+
+        // the body may be empty, so the arm
+        // ends with the last token we consumed
+        let end_loc = self.prev_span.unwrap().end;
+
+        let kind = SyntaxKind::CaseArm {
+            value: Box::new(value),
+            body: Box::new(body),
+        };
+
+        let span = Span::from_loc(keyword.span.start, end_loc);
+        let token = SyntaxToken::new(kind, span);
+        Some(token)
+    }
+
+    fn parse_return_statement(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
+        let keyword = self.next_token().unwrap();
+        let num_errors = errors.errors.len();
+
+        let (expr, end_loc) = match self.parse_expression(errors) {
+            Some(e) => {
+                let end_loc = e.span.end.clone();
+                (Some(Box::new(e)), end_loc)
+            }
+            None => {
+                if errors.errors.len() != num_errors {
+                    // there was a return expression,
+                    // but it's malformed
+                    return None;
+                }
+
+                (None, keyword.span.end)
+            }
+        };
+
         let span = Span::from_loc(keyword.span.start, end_loc);
         let kind = SyntaxKind::ReturnStatement { expr: expr };
 
@@ -523,35 +1119,30 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_function_declaration(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
-        let keyword = self.tokens.next().unwrap();
+        let keyword = self.next_token().unwrap();
         let start_loc = keyword.span.start.clone();
+        let header_line = keyword.span.start.line;
 
         // identifier
         let identifier = match self.parse_identifier() {
             Some((i, _)) => i,
             None => {
                 let error_kind = ErrorKind::ExpectedToken("identifier".to_string());
-                errors.add(error_kind, keyword.span);
-                return None;
+                errors.add(error_kind, self.expected_span());
+                return self.parse_broken_block(start_loc, header_line, errors);
             }
         };
 
         // parameter list
         let parameters = match self.parse_parameter_list(errors) {
             Some(p) => p,
-            None => return None,
+            None => return self.parse_broken_block(start_loc, header_line, errors),
         };
 
-        let mut return_type: Option<String> = None;
+        let mut return_type: Option<TypeSyntax> = None;
         if self.expect_next_token_peek(LexerTokenKind::ArrowToken) {
-            let arrow = self.tokens.next().unwrap();
-            match self.parse_identifier() {
-                Some((i, _)) => return_type = Some(i.clone()),
-                None => {
-                    let error_kind = ErrorKind::ExpectedToken("identifier".to_string());
-                    errors.add(error_kind, arrow.span);
-                }
-            };
+            self.next_token();
+            return_type = self.parse_type("identifier", errors);
         }
 
         // body
@@ -565,27 +1156,51 @@ impl<'a> Parser<'a> {
         };
 
         // end keyword
-        match self.tokens.next() {
-            Some(t) => match &t.kind {
-                LexerTokenKind::EndKeyword => {}
-                _ => {
-                    let error_kind = ErrorKind::ExpectedToken("end".to_string());
-                    errors.add(error_kind, keyword.span);
-                    return None;
-                }
-            },
+        if !self.expect_next_token(LexerTokenKind::EndKeyword) {
+            let error_kind = ErrorKind::ExpectedToken("end".to_string());
+            errors.add(error_kind, self.expected_span());
+        }
+
+        let end_loc = body.span.end.clone();
+        let kind = SyntaxKind::FunctionDeclaration {
+            identifier: identifier,
+            parameters: parameters,
+            return_type: return_type,
+            body: Box::new(body),
+        };
+
+        let span = Span::from_loc(start_loc, end_loc);
+        let token = SyntaxToken::new(kind, span);
+        Some(token)
+    }
+
+    fn parse_constructor_declaration(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
+        let keyword = self.next_token().unwrap();
+        let start_loc = keyword.span.start.clone();
+        let header_line = keyword.span.start.line;
+
+        let parameters = match self.parse_parameter_list(errors) {
+            Some(p) => p,
+            None => return self.parse_broken_block(start_loc, header_line, errors),
+        };
+
+        let body = match self.parse_scope(errors) {
+            Some(b) => b,
             None => {
-                let error_kind = ErrorKind::ExpectedToken("end".to_string());
+                let error_kind = ErrorKind::ExpectedToken("constructor body".to_string());
                 errors.add(error_kind, keyword.span);
                 return None;
             }
         };
 
+        if !self.expect_next_token(LexerTokenKind::EndKeyword) {
+            let error_kind = ErrorKind::ExpectedToken("end".to_string());
+            errors.add(error_kind, self.expected_span());
+        }
+
         let end_loc = body.span.end.clone();
-        let kind = SyntaxKind::FunctionDeclaration {
-            identifier: identifier,
+        let kind = SyntaxKind::ConstructorDeclaration {
             parameters: parameters,
-            return_type: return_type,
             body: Box::new(body),
         };
 
@@ -594,13 +1209,92 @@ impl<'a> Parser<'a> {
         Some(token)
     }
 
+    fn parse_class_member(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
+        if !self.expect_next_token_peek(LexerTokenKind::PrivateKeyword) {
+            return self.parse_class_member_declaration(errors);
+        }
+
+        let keyword = self.next_token().unwrap();
+        let member = match self.parse_class_member_declaration(errors) {
+            Some(m) => m,
+            None => return None,
+        };
+
+        let span = Span::from_loc(keyword.span.start, member.span.end);
+        let kind = SyntaxKind::PrivateMember {
+            member: Box::new(member),
+        };
+
+        let token = SyntaxToken::new(kind, span);
+        Some(token)
+    }
+
+    fn parse_class_member_declaration(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
+        let Some(peek) = self.tokens.peek() else {
+            return None;
+        };
+
+        match peek.kind {
+            LexerTokenKind::FunctionKeyword => self.parse_function_declaration(errors),
+            LexerTokenKind::ConstructorKeyword => self.parse_constructor_declaration(errors),
+            LexerTokenKind::IdentifierToken(_) => {
+                // fields are declared like variables, i.e. x: Int = 0
+                let (identifier, identifier_span) = self.parse_identifier().unwrap();
+                self.parse_variable_declaration(identifier, identifier_span, errors)
+            }
+            _ => None,
+        }
+    }
+
+    fn parse_class_declaration(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
+        let keyword = self.next_token().unwrap();
+        let start_loc = keyword.span.start.clone();
+        let header_line = keyword.span.start.line;
+
+        let identifier = match self.parse_identifier() {
+            Some((i, _)) => i,
+            None => {
+                let error_kind = ErrorKind::ExpectedToken("identifier".to_string());
+                errors.add(error_kind, self.expected_span());
+                return self.parse_broken_block(start_loc, header_line, errors);
+            }
+        };
+
+        let mut parent = None;
+        if self.expect_next_token(LexerTokenKind::ExtendsKeyword) {
+            match self.parse_identifier() {
+                Some((p, _)) => parent = Some(p),
+                None => {
+                    let error_kind = ErrorKind::ExpectedToken("parent class".to_string());
+                    errors.add(error_kind, self.expected_span());
+                    return self.parse_broken_block(start_loc, header_line, errors);
+                }
+            }
+        }
+
+        let members = self.parse_list(Self::parse_class_member, errors);
+
+        if !self.expect_next_token(LexerTokenKind::EndKeyword) {
+            let error_kind = ErrorKind::ExpectedToken("end".to_string());
+            errors.add(error_kind, self.expected_span());
+        }
+
+        let end_loc = self.prev_span.unwrap().end;
+        let kind = SyntaxKind::ClassDeclaration {
+            identifier: identifier,
+            parent: parent,
+            members: members,
+        };
+
+        let span = Span::from_loc(start_loc, end_loc);
+        let token = SyntaxToken::new(kind, span);
+        Some(token)
+    }
+
     fn parse_parameter_list(&mut self, errors: &mut ErrorBag) -> Option<Vec<SyntaxToken>> {
         if !self.expect_next_token(LexerTokenKind::OpenParenthesisToken) {
-            // TODO: Use last token span to report error
             let error_kind = ErrorKind::ExpectedToken("open parenthesis '('".to_string());
-
-            let span = Span::new(0, 0, 0, 0, 0, 0);
-            errors.add(error_kind, span);
+            errors.add(error_kind, self.expected_span());
             return None;
         }
 
@@ -610,7 +1304,11 @@ impl<'a> Parser<'a> {
         loop {
             let peek = match self.tokens.peek() {
                 Some(t) => t.clone(),
-                None => return None,
+                None => {
+                    let error_kind = ErrorKind::ExpectedToken("close parenthesis ')'".to_string());
+                    errors.add(error_kind, self.expected_span());
+                    return None;
+                }
             };
 
             match peek.kind {
@@ -619,48 +1317,47 @@ impl<'a> Parser<'a> {
                         // comma, but no further
                         // params provided
                         let error_kind = ErrorKind::ExpectedParameter;
-                        errors.add(error_kind, peek.span);
+                        errors.add(error_kind, self.expected_span());
                         return None;
                     }
 
-                    self.tokens.next();
+                    self.next_token();
                     break;
                 }
-                LexerTokenKind::IdentifierToken(_) => {}
+                LexerTokenKind::IdentifierToken(_) | LexerTokenKind::RefKeyword => {}
                 _ => {
                     // expected identifier
                     let error_kind = ErrorKind::ExpectedToken("identifier".to_string());
-                    errors.add(error_kind, peek.span);
+                    errors.add(error_kind, self.expected_span());
                     return None;
                 }
             };
 
+            let is_ref = self.expect_next_token(LexerTokenKind::RefKeyword);
             let (identifier, loc) = match self.parse_identifier() {
                 Some(i) => i,
-                None => return None,
-            };
-
-            if !self.expect_next_token(LexerTokenKind::ColonToken) {
-                let error_kind = ErrorKind::ExpectedToken("type annotation".to_string());
-                errors.add(error_kind, peek.span);
-                return None;
-            }
-
-            let type_annotation = match self.parse_identifier() {
-                Some((i, _)) => i,
                 None => {
-                    let error_kind = ErrorKind::ExpectedToken("type annotation".to_string());
-                    errors.add(error_kind, peek.span);
+                    let error_kind = ErrorKind::ExpectedToken("identifier".to_string());
+                    errors.add(error_kind, self.expected_span());
                     return None;
                 }
             };
 
+            // the type annotation is optional
+            let mut type_annotation = None;
+            if self.expect_next_token(LexerTokenKind::ColonToken) {
+                type_annotation = match self.parse_type("type annotation", errors) {
+                    Some(t) => Some(t),
+                    None => return None,
+                };
+            }
+
             prev_comma = false;
             match self.tokens.peek() {
                 Some(t) => match t.kind {
                     LexerTokenKind::CommaToken => {
                         prev_comma = true;
-                        self.tokens.next();
+                        self.next_token();
                     }
                     LexerTokenKind::CloseParenthesisToken => {}
                     _ => {
@@ -669,7 +1366,7 @@ impl<'a> Parser<'a> {
                             "comma ',' or close parenthesis ')'".to_string(),
                         );
 
-                        errors.add(error_kind, t.span);
+                        errors.add(error_kind, self.expected_span());
                         return None;
                     }
                 },
@@ -679,6 +1376,7 @@ impl<'a> Parser<'a> {
             let kind = SyntaxKind::Parameter {
                 identifier: identifier,
                 type_annotation: type_annotation,
+                is_ref: is_ref,
             };
 
             let token = SyntaxToken::new(kind, loc);
@@ -689,103 +1387,110 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_loop(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
-        let loop_token = self.tokens.next().unwrap();
+        let loop_token = self.next_token().unwrap();
+        let start_loc = loop_token.span.start;
+        let header_line = loop_token.span.start.line;
+
         match self.tokens.peek() {
             Some(p) => match p.kind {
                 LexerTokenKind::IdentifierToken(_) => self.parse_for_loop(errors),
                 LexerTokenKind::WhileKeyword => self.parse_while_loop(errors),
                 _ => {
                     let kind = ErrorKind::ExpectedLoop;
-                    errors.add(kind, loop_token.span);
-                    return None;
+                    errors.add(kind, self.expected_span());
+                    return self.parse_broken_block(start_loc, header_line, errors);
                 }
             },
             None => {
                 let kind = ErrorKind::ExpectedLoop;
-                errors.add(kind, loop_token.span);
+                errors.add(kind, self.expected_span());
                 return None;
             }
         }
     }
 
     fn parse_for_loop(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
-        let for_keyword = self.tokens.next().unwrap();
-        let (identifier, identifier_span) = match self.parse_identifier() {
+        let for_keyword = self.next_token().unwrap();
+        let start_loc = for_keyword.span.start;
+        let header_line = for_keyword.span.start.line;
+
+        let (identifier, _) = match self.parse_identifier() {
             Some(i) => i,
             None => {
                 let kind = ErrorKind::ExpectedToken("identifier".to_string());
-                errors.add(kind, for_keyword.span);
-                return None;
+                errors.add(kind, self.expected_span());
+                return self.parse_broken_block(start_loc, header_line, errors);
             }
         };
 
         if !self.expect_next_token(LexerTokenKind::FromKeyword) {
             let kind = ErrorKind::ExpectedToken("from keyword".to_string());
-            errors.add(kind, identifier_span);
-            return None;
+            errors.add(kind, self.expected_span());
+            return self.parse_broken_block(start_loc, header_line, errors);
         }
 
-        let (lower_bound, lower_bound_span) = match self.tokens.next() {
-            Some(t) => match t.kind {
-                LexerTokenKind::IntegerLiteralToken(val) => (val, t.span),
-                _ => {
-                    let kind = ErrorKind::ExpectedLoopLowerBound;
-                    errors.add(kind, identifier_span);
-                    return None;
-                }
-            },
+        let lower_bound = match self.parse_expression(errors) {
+            Some(e) => e,
             None => {
                 let kind = ErrorKind::ExpectedLoopLowerBound;
-                errors.add(kind, identifier_span);
-                return None;
+                errors.add(kind, self.expected_span());
+                return self.parse_broken_block(start_loc, header_line, errors);
             }
         };
 
         if !self.expect_next_token(LexerTokenKind::ToKeyword) {
             let kind = ErrorKind::ExpectedToken("to keyword".to_string());
-            errors.add(kind, lower_bound_span);
-            return None;
+            errors.add(kind, self.expected_span());
+            return self.parse_broken_block(start_loc, header_line, errors);
         }
 
-        let (upper_bound, upper_bound_span) = match self.tokens.next() {
-            Some(t) => {
-                let span = t.span.clone();
-                match t.kind {
-                    LexerTokenKind::IntegerLiteralToken(val) => (val, span),
-                    _ => {
-                        let kind = ErrorKind::ExpectedLoopUpperBound;
-                        errors.add(kind, identifier_span);
-                        return None;
-                    }
-                }
-            }
+        let upper_bound = match self.parse_expression(errors) {
+            Some(e) => e,
             None => {
                 let kind = ErrorKind::ExpectedLoopUpperBound;
-                errors.add(kind, identifier_span);
-                return None;
+                errors.add(kind, self.expected_span());
+                return self.parse_broken_block(start_loc, header_line, errors);
             }
         };
 
+        let mut header_end_span = upper_bound.span.clone();
+        let step = if self.expect_next_token_peek(LexerTokenKind::StepKeyword) {
+            self.next_token();
+            match self.parse_expression(errors) {
+                Some(e) => {
+                    header_end_span = e.span.clone();
+                    Some(Box::new(e))
+                }
+                None => {
+                    let kind = ErrorKind::ExpectedLoopStep;
+                    errors.add(kind, self.expected_span());
+                    return self.parse_broken_block(start_loc, header_line, errors);
+                }
+            }
+        } else {
+            None
+        };
+
         let body = match self.parse_scope(errors) {
             Some(b) => b,
             None => {
                 let error_kind = ErrorKind::ExpectedScope;
-                errors.add(error_kind, upper_bound_span);
+                errors.add(error_kind, header_end_span);
                 return None;
             }
         };
 
         if !self.expect_next_token(LexerTokenKind::EndKeyword) {
             let error_kind = ErrorKind::ExpectedToken("end keyword".to_string());
-            errors.add(error_kind, body.span);
-            return None;
+            errors.add(error_kind, self.expected_span());
         }
 
         let end_loc = body.span.end.clone();
         let kind = SyntaxKind::ForLoop {
             identifier: identifier,
-            lower_bound: lower_bound as usize,
-            upper_bound: upper_bound as usize,
+            lower_bound: Box::new(lower_bound),
+            upper_bound: Box::new(upper_bound),
+            step: step,
             body: Box::new(body),
         };
 
@@ -795,11 +1500,20 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_while_loop(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
-        let while_keyword = self.tokens.next().unwrap();
+        let while_keyword = self.next_token().unwrap();
+        let start_loc = while_keyword.span.start;
+        let header_line = while_keyword.span.start.line;
 
+        let num_errors = errors.errors.len();
         let expr = match self.parse_expression(errors) {
             Some(e) => e,
-            None => return None,
+            None => {
+                if errors.errors.len() == num_errors {
+                    let error_kind = ErrorKind::ExpectedToken("condition expression".to_string());
+                    errors.add(error_kind, self.expected_span());
+                }
+                return self.parse_broken_block(start_loc, header_line, errors);
+            }
         };
 
         let body = match self.parse_scope(errors) {
@@ -813,8 +1527,7 @@ impl<'a> Parser<'a> {
 
         if !self.expect_next_token(LexerTokenKind::EndKeyword) {
             let error_kind = ErrorKind::ExpectedToken("end keyword".to_string());
-            errors.add(error_kind, body.span);
-            return None;
+            errors.add(error_kind, self.expected_span());
         }
 
         let end_loc = body.span.end.clone();
@@ -828,6 +1541,18 @@ impl<'a> Parser<'a> {
         Some(token)
     }
 
+    fn parse_break_statement(&mut self) -> Option<SyntaxToken> {
+        let keyword = self.next_token().unwrap();
+        let token = SyntaxToken::new(SyntaxKind::BreakStatement, keyword.span);
+        Some(token)
+    }
+
+    fn parse_continue_statement(&mut self) -> Option<SyntaxToken> {
+        let keyword = self.next_token().unwrap();
+        let token = SyntaxToken::new(SyntaxKind::ContinueStatement, keyword.span);
+        Some(token)
+    }
+
     fn parse_statement(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
         let peek = self.tokens.peek();
 
@@ -835,31 +1560,81 @@ impl<'a> Parser<'a> {
         match peek.kind {
             LexerTokenKind::OutputKeyword => self.parse_output_statement(errors),
             LexerTokenKind::IfKeyword => self.parse_if_statement(errors),
+            LexerTokenKind::CaseKeyword => self.parse_case_statement(errors),
             LexerTokenKind::ReturnKeyword => self.parse_return_statement(errors),
             LexerTokenKind::FunctionKeyword => self.parse_function_declaration(errors),
+            LexerTokenKind::ClassKeyword => self.parse_class_declaration(errors),
+            LexerTokenKind::ConstantKeyword => self.parse_constant_declaration(errors),
             LexerTokenKind::LoopKeyword => self.parse_loop(errors),
+            LexerTokenKind::BreakKeyword => self.parse_break_statement(),
+            LexerTokenKind::ContinueKeyword => self.parse_continue_statement(),
             _ => self.parse_expression(errors),
         }
     }
 
-    fn parse_scope(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
+    fn parse_statement_list(&mut self, errors: &mut ErrorBag) -> Vec<SyntaxToken> {
+        self.parse_list(Self::parse_statement, errors)
+    }
+
+    fn parse_list(
+        &mut self,
+        parse_item: fn(&mut Self, &mut ErrorBag) -> Option<SyntaxToken>,
+        errors: &mut ErrorBag,
+    ) -> Vec<SyntaxToken> {
         let mut parsed: Vec<SyntaxToken> = Vec::new();
         loop {
-            let statement = self.parse_statement(errors);
-            match statement {
-                Some(s) => parsed.push(s),
+            let start = match self.tokens.peek() {
+                Some(p) => {
+                    if p.kind.is_scope_end() {
+                        break;
+                    }
+
+                    *p
+                }
                 None => break,
             };
+
+            let start_position = self.position;
+            let num_errors = errors.errors.len();
+
+            match parse_item(self, errors) {
+                Some(s) => parsed.push(s),
+                None => {
+                    if errors.errors.len() == num_errors {
+                        // the statement failed without
+                        // telling us why, report the token
+                        // we got stuck on
+                        self.report_unexpected_token(errors);
+                    }
+
+                    if self.position == start_position {
+                        // always make progress, otherwise
+                        // we would try to parse the same
+                        // token over and over again
+                        self.next_token();
+                    }
+
+                    self.synchronize();
+                    let error = self.error_node(start.span.start);
+                    parsed.push(error);
+                }
+            };
         }
 
+        parsed
+    }
+
+    fn parse_scope(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
+        let parsed = self.parse_statement_list(errors);
+
         let first_loc = match parsed.first() {
             Some(f) => f.span.start.clone(),
-            None => Location::new(0, 0, 0),
+            None => self.file_start(),
         };
 
         let last_loc = match parsed.last() {
             Some(l) => l.span.end.clone(),
-            None => Location::new(0, 0, 0),
+            None => self.file_start(),
         };
 
         let span = Span::from_loc(first_loc, last_loc);
@@ -870,9 +1645,13 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_identifier(&mut self) -> Option<(String, Span)> {
-        match self.tokens.next() {
+        match self.tokens.peek() {
             Some(t) => match &t.kind {
-                LexerTokenKind::IdentifierToken(id) => Some((id.clone(), t.span.clone())),
+                LexerTokenKind::IdentifierToken(id) => {
+                    let identifier = (id.clone(), t.span.clone());
+                    self.next_token();
+                    Some(identifier)
+                }
                 _ => return None,
             },
             None => return None,
@@ -880,7 +1659,19 @@ impl<'a> Parser<'a> {
     }
 
     fn expect_next_token(&mut self, kind: LexerTokenKind) -> bool {
-        match self.tokens.next() {
+        // only consume the token if it matches, so
+        // that a missing token doesn't swallow the
+        // start of the next construct
+        if self.expect_next_token_peek(kind) {
+            self.next_token();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_next_token_peek(&mut self, kind: LexerTokenKind) -> bool {
+        match self.tokens.peek() {
             Some(t) => {
                 if t.kind == kind {
                     true
@@ -892,23 +1683,205 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn expect_next_token_peek(&mut self, kind: LexerTokenKind) -> bool {
+    fn report_unexpected_token(&mut self, errors: &mut ErrorBag) {
         match self.tokens.peek() {
             Some(t) => {
-                if t.kind == kind {
-                    true
-                } else {
-                    false
+                let error_kind = ErrorKind::UnexpectedToken(t.kind.to_string());
+                errors.add(error_kind, t.span);
+            }
+            None => {
+                errors.add(ErrorKind::UnexpectedEndOfFile, self.expected_span());
+            }
+        }
+    }
+
+    fn expected_span(&self) -> Span {
+        // a missing token is expected right
+        // after the last token that was parsed
+        let loc = match self.prev_span {
+            Some(s) => s.end,
+            None => self.file_start(),
+        };
+
+        Span::from_loc(loc, loc)
+    }
+
+    fn synchronize(&mut self) {
+        // panic mode, skip tokens until we reach
+        // something that can start a new statement
+        // or close the current scope. statements end
+        // with their line, so the next line is a new one
+        let line = match self.prev_span {
+            Some(s) => s.end.line,
+            None => return,
+        };
+
+        self.synchronize_header(line, None);
+    }
+
+    fn synchronize_header(&mut self, line: usize, until: Option<LexerTokenKind>) {
+        // skip the rest of a broken block header,
+        // block bodies always start on a new line
+        while let Some(p) = self.tokens.peek() {
+            if p.span.start.line != line
+                || Some(&p.kind) == until.as_ref()
+                || p.kind.is_statement_start()
+                || p.kind.is_scope_end()
+            {
+                break;
+            }
+
+            self.next_token();
+        }
+    }
+
+    fn parse_broken_block(
+        &mut self,
+        start_loc: Location,
+        header_line: usize,
+        errors: &mut ErrorBag,
+    ) -> Option<SyntaxToken> {
+        // the header of a block construct couldn't
+        // be parsed, skip it but still parse the body
+        // so that its end doesn't close the parent scope
+        self.synchronize_header(header_line, None);
+        self.parse_scope(errors);
+
+        if !self.expect_next_token(LexerTokenKind::EndKeyword) {
+            let error_kind = ErrorKind::ExpectedToken("end keyword".to_string());
+            errors.add(error_kind, self.expected_span());
+        }
+
+        Some(self.error_node(start_loc))
+    }
+
+    fn error_node(&self, start_loc: Location) -> SyntaxToken {
+        let end_loc = match self.prev_span {
+            Some(s) => s.end,
+            None => start_loc,
+        };
+
+        let span = Span::from_loc(start_loc, end_loc);
+        SyntaxToken::new(SyntaxKind::Error, span)
+    }
+
+    fn parse_import_statement(&mut self, errors: &mut ErrorBag) -> Option<SyntaxToken> {
+        let keyword = self.next_token().unwrap();
+        let start_loc = keyword.span.start.clone();
+
+        let module = match self.tokens.peek() {
+            Some(LexerToken {
+                kind: LexerTokenKind::StringLiteralToken(m),
+                ..
+            }) => m.clone(),
+            _ => {
+                let error_kind = ErrorKind::ExpectedToken("module name".to_string());
+                errors.add(error_kind, self.expected_span());
+                return None;
+            }
+        };
+
+        let module_token = self.next_token().unwrap();
+        let span = Span::from_loc(start_loc, module_token.span.end);
+        let kind = SyntaxKind::ImportStatement { module: module };
+
+        let token = SyntaxToken::new(kind, span);
+        Some(token)
+    }
+
+    fn parse_module(&mut self, trivia: Vec<Trivia>, errors: &mut ErrorBag) -> SyntaxToken {
+        // imports have to come before any other statement,
+        // so imported modules are loaded before anything runs
+        let mut imports: Vec<SyntaxToken> = Vec::new();
+        while let Some(peek) = self.tokens.peek() {
+            if peek.kind != LexerTokenKind::ImportKeyword {
+                break;
+            }
+
+            let start_loc = peek.span.start;
+            match self.parse_import_statement(errors) {
+                Some(i) => imports.push(i),
+                None => {
+                    self.synchronize();
+                    let error = self.error_node(start_loc);
+                    imports.push(error);
                 }
             }
-            None => false,
         }
+
+        let mut parsed: Vec<SyntaxToken> = Vec::new();
+        loop {
+            parsed.append(&mut self.parse_statement_list(errors));
+
+            // the statement list only stops at the end
+            // of the file or at a keyword that closes
+            // a scope, which doesn't close anything here
+            let start_loc = match self.tokens.peek() {
+                Some(p) => p.span.start,
+                None => break,
+            };
+
+            self.report_unexpected_token(errors);
+            self.next_token();
+
+            let error = self.error_node(start_loc);
+            parsed.push(error);
+        }
+
+        let first_loc = match parsed.first() {
+            Some(f) => f.span.start.clone(),
+            None => self.file_start(),
+        };
+
+        let last_loc = match parsed.last() {
+            Some(l) => l.span.end.clone(),
+            None => self.file_start(),
+        };
+
+        let span = Span::from_loc(first_loc, last_loc);
+
+        let scope_kind = SyntaxKind::Scope { subtokens: parsed };
+        let block = SyntaxToken::new(scope_kind, span);
+
+        let first_loc = match imports.first() {
+            Some(i) => i.span.start.clone(),
+            None => block.span.start.clone(),
+        };
+
+        let span = Span::from_loc(first_loc, block.span.end.clone());
+        let kind = SyntaxKind::Module {
+            imports: imports,
+            block: Box::new(block),
+            trivia: trivia,
+        };
+
+        SyntaxToken::new(kind, span)
     }
 }
 
-pub fn parse(tokens: Vec<LexerToken>, errors: &mut ErrorBag) -> Option<SyntaxToken> {
-    let iter = tokens.iter().peekable();
-    let mut parser = Parser::new(iter);
+pub fn parse(
+    tokens: Vec<LexerToken>,
+    trailing_trivia: Vec<Trivia>,
+    file: FileId,
+    errors: &mut ErrorBag,
+) -> SyntaxToken {
+    // split tokens carry the trivia of the `>=` they came from,
+    // so it's collected from the unsplit tokens
+    let mut trivia: Vec<Trivia> = vec![];
+    for token in tokens.iter() {
+        trivia.extend(token.leading_trivia.iter().cloned());
+        trivia.extend(token.trailing_trivia.iter().cloned());
+    }
+    trivia.extend(trailing_trivia);
+
+    // split up front, so they live as long as the other tokens
+    let splits: Vec<(LexerToken, LexerToken)> = tokens
+        .iter()
+        .filter(|t| t.kind == LexerTokenKind::GreaterThanEqualsToken)
+        .map(|t| t.split_greater_than_equals())
+        .collect();
+
+    let mut parser = Parser::new(lexer_tokens(tokens.iter()), &splits, file);
 
-    parser.parse_scope(errors)
+    parser.parse_module(trivia, errors)
 }
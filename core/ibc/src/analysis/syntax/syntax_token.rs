@@ -1,5 +1,7 @@
 use crate::analysis::{operator::Operator, span::Span};
 
+use super::lexer::Trivia;
+
 #[derive(Debug)]
 pub struct SyntaxToken {
     pub kind: SyntaxKind,
@@ -15,6 +17,14 @@ impl SyntaxToken {
     }
 }
 
+// a type annotation, i.e. Int or Array<Stack<String>>
+#[derive(Debug, Clone)]
+pub struct TypeSyntax {
+    pub identifier: String,
+    pub generic: Option<Box<TypeSyntax>>,
+    pub span: Span,
+}
+
 #[derive(Debug)]
 pub enum SyntaxKind {
     Scope {
@@ -26,8 +36,12 @@ pub enum SyntaxKind {
         next: Box<SyntaxToken>,
     },
     IntegerLiteralExpression(i64),
+    RealLiteralExpression(f64),
     BooleanLiteralExpression(bool),
     StringLiteralExpression(String),
+    InterpolatedStringExpression {
+        parts: Vec<SyntaxToken>,
+    },
     BinaryExpression {
         lhs: Box<SyntaxToken>,
         op: Operator,
@@ -45,12 +59,28 @@ pub enum SyntaxKind {
         identifier: String,
         value: Box<SyntaxToken>,
     },
+    VariableDeclaration {
+        identifier: String,
+        type_annotation: TypeSyntax,
+        value: Option<Box<SyntaxToken>>,
+    },
+    ArrayLiteralExpression {
+        elements: Vec<SyntaxToken>,
+    },
+    IndexExpression {
+        base: Box<SyntaxToken>,
+        index: Box<SyntaxToken>,
+    },
+    IndexAssignmentExpression {
+        base: Box<SyntaxToken>,
+        index: Box<SyntaxToken>,
+        value: Box<SyntaxToken>,
+    },
     ParenthesizedExpression {
         inner: Box<SyntaxToken>,
     },
     InstantiationExpression {
-        type_name: String,
-        type_param: Option<String>,
+        type_syntax: TypeSyntax,
         args: Vec<SyntaxToken>,
     },
     OutputStatement {
@@ -59,15 +89,53 @@ pub enum SyntaxKind {
     IfStatement {
         condition: Box<SyntaxToken>,
         body: Box<SyntaxToken>,
+        else_body: Option<Box<SyntaxToken>>,
+    },
+    CaseStatement {
+        expr: Box<SyntaxToken>,
+        arms: Vec<SyntaxToken>,
+        otherwise: Option<Box<SyntaxToken>>,
+    },
+    CaseArm {
+        value: Box<SyntaxToken>,
+        body: Box<SyntaxToken>,
     },
     Parameter {
         identifier: String,
-        type_annotation: String,
+        // inferred by the binder when left out
+        type_annotation: Option<TypeSyntax>,
+        // the argument is written back once the call returns
+        is_ref: bool,
     },
     FunctionDeclaration {
         identifier: String,
         parameters: Vec<SyntaxToken>,
-        return_type: Option<String>,
+        return_type: Option<TypeSyntax>,
+        body: Box<SyntaxToken>,
+    },
+    ClassDeclaration {
+        identifier: String,
+        parent: Option<String>,
+        members: Vec<SyntaxToken>,
+    },
+    Module {
+        imports: Vec<SyntaxToken>,
+        block: Box<SyntaxToken>,
+        // every comment in the file, in source order
+        trivia: Vec<Trivia>,
+    },
+    ImportStatement {
+        module: String,
+    },
+    ConstantDeclaration {
+        identifier: String,
+        value: Box<SyntaxToken>,
+    },
+    PrivateMember {
+        member: Box<SyntaxToken>,
+    },
+    ConstructorDeclaration {
+        parameters: Vec<SyntaxToken>,
         body: Box<SyntaxToken>,
     },
     ReturnStatement {
@@ -75,12 +143,16 @@ pub enum SyntaxKind {
     },
     ForLoop {
         identifier: String,
-        lower_bound: usize,
-        upper_bound: usize,
+        lower_bound: Box<SyntaxToken>,
+        upper_bound: Box<SyntaxToken>,
+        step: Option<Box<SyntaxToken>>,
         body: Box<SyntaxToken>,
     },
     WhileLoop {
         expr: Box<SyntaxToken>,
         body: Box<SyntaxToken>,
     },
+    BreakStatement,
+    ContinueStatement,
+    Error,
 }
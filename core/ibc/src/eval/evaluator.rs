@@ -1,4 +1,5 @@
 use std::{
+    cmp::Ordering,
     collections::HashMap,
     mem,
     sync::{Arc, Mutex},
@@ -8,44 +9,162 @@ use async_recursion::async_recursion;
 
 use crate::analysis::{
     binding::{
-        bound_node::{BoundNode, BoundNodeKind},
-        symbols::{FunctionSymbol, VariableSymbol},
-        types::{get_object_state, ObjectState},
+        bound_node::{BoundFieldInitializer, BoundNode, BoundNodeKind},
+        symbols::{ClassSymbol, FieldSymbol, FunctionSymbol, MethodSymbol, VariableSymbol},
+        types::{get_object_state, ArrayState, InstanceState, ObjectState, TypeKind},
     },
     operator::Operator,
+    span::Span,
 };
 
-use super::{eval_builtin, object_methods::eval_type_method, EvalIO};
+use super::{
+    eval_builtin,
+    object_methods::{eval_type_method, resolve_instance_method},
+    EvalIO,
+};
+
+pub struct RuntimeError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl RuntimeError {
+    pub fn new(message: String, span: Span) -> RuntimeError {
+        RuntimeError {
+            message: message,
+            span: span,
+        }
+    }
+
+    pub fn format(&self) -> String {
+        format!(
+            "{} on line: {}, column: {}",
+            self.message, self.span.start.line, self.span.start.col
+        )
+    }
+}
+
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 200;
+
+// every call nests several evaluation frames on the host stack,
+// threads running the evaluator need this much to reach the
+// default call depth, even in debug builds
+pub const EVAL_STACK_SIZE: usize = 64 * 1024 * 1024;
 
 pub struct EvalInfo {
     pub heap: EvalHeap,
+    max_call_depth: usize,
+}
+
+struct EvalFrame {
+    depth: usize,
+    variables: HashMap<u64, EvalValue>,
+}
+
+impl EvalFrame {
+    fn new(depth: usize) -> EvalFrame {
+        EvalFrame {
+            depth: depth,
+            variables: HashMap::new(),
+        }
+    }
+}
+
+struct EvalClass {
+    symbol: ClassSymbol,
+    initializers: Arc<Vec<BoundFieldInitializer>>,
 }
 
 pub struct EvalHeap {
     // just use rust's heap to manage
     // memory, no need for us to make
     // our own heap
-    variables: HashMap<u64, EvalValue>,
+    frames: Vec<EvalFrame>,
     functions: HashMap<u64, Arc<BoundNode>>,
+    // classes by id, with their field initializers
+    classes: HashMap<u64, EvalClass>,
 }
 
 impl EvalHeap {
     fn new() -> EvalHeap {
         EvalHeap {
-            variables: HashMap::new(),
+            frames: vec![EvalFrame::new(0)],
             functions: HashMap::new(),
+            classes: HashMap::new(),
         }
     }
 
-    pub fn assign_var(&mut self, symbol: &VariableSymbol, val: EvalValue) {
+    fn call_depth(&self) -> usize {
+        // the global frame isn't a call
+        self.frames.len() - 1
+    }
+
+    fn push_frame(&mut self, depth: usize) {
+        self.frames.push(EvalFrame::new(depth));
+    }
+
+    fn pop_frame(&mut self) {
+        self.frames.pop();
+    }
+
+    // a variable lives in the closest frame of the function body
+    // it was declared in, nested functions can only be called
+    // while that frame is somewhere on the stack
+    fn frame_of(
+        &mut self,
+        symbol: &VariableSymbol,
+        span: Span,
+    ) -> Result<&mut EvalFrame, RuntimeError> {
+        let frame = self
+            .frames
+            .iter_mut()
+            .rev()
+            .find(|f| f.depth == symbol.frame_depth);
+
+        match frame {
+            Some(f) => Ok(f),
+            None => {
+                let message = format!("Variable '{}' is no longer in scope", symbol.identifier);
+                Err(RuntimeError::new(message, span))
+            }
+        }
+    }
+
+    pub fn assign_var(
+        &mut self,
+        symbol: &VariableSymbol,
+        val: EvalValue,
+        span: Span,
+    ) -> Result<(), RuntimeError> {
         let id = symbol.symbol_id;
-        self.variables.insert(id, val);
+        self.frame_of(symbol, span)?.variables.insert(id, val);
+        Ok(())
+    }
+
+    pub fn get_var(
+        &mut self,
+        symbol: &VariableSymbol,
+        span: Span,
+    ) -> Result<EvalValue, RuntimeError> {
+        match self.try_get_var(symbol, span)? {
+            Some(v) => Ok(v),
+            None => {
+                let message = format!(
+                    "Variable '{}' is used before it's assigned",
+                    symbol.identifier
+                );
+                Err(RuntimeError::new(message, span))
+            }
+        }
     }
 
-    pub fn get_var(&self, symbol: &VariableSymbol) -> EvalValue {
+    pub fn try_get_var(
+        &mut self,
+        symbol: &VariableSymbol,
+        span: Span,
+    ) -> Result<Option<EvalValue>, RuntimeError> {
         let id = &symbol.symbol_id;
-        let value = &self.variables[id];
-        value.clone()
+        Ok(self.frame_of(symbol, span)?.variables.get(id).cloned())
     }
 
     pub fn declare_func(&mut self, symbol: &FunctionSymbol, body: Arc<BoundNode>) {
@@ -53,6 +172,36 @@ impl EvalHeap {
         self.functions.insert(id, body);
     }
 
+    pub fn declare_class(
+        &mut self,
+        symbol: &ClassSymbol,
+        initializers: Arc<Vec<BoundFieldInitializer>>,
+    ) {
+        let class = EvalClass {
+            symbol: symbol.clone(),
+            initializers: initializers,
+        };
+
+        self.classes.insert(symbol.class_type.class_id, class);
+    }
+
+    pub fn get_initializers(&self, class_id: u64) -> Vec<Arc<Vec<BoundFieldInitializer>>> {
+        // the parent's fields are initialized first
+        let class = &self.classes[&class_id];
+        let mut initializers = match &class.symbol.parent {
+            Some(p) => self.get_initializers(p.class_id),
+            None => Vec::new(),
+        };
+
+        initializers.push(class.initializers.clone());
+        initializers
+    }
+
+    pub fn get_method(&self, class_id: u64, identifier: &str) -> MethodSymbol {
+        let class = &self.classes[&class_id];
+        class.symbol.get_method(identifier).unwrap().clone()
+    }
+
     pub fn get_func(&self, symbol: &FunctionSymbol) -> Arc<BoundNode> {
         let id = &symbol.symbol_id;
         let body = &self.functions[id];
@@ -64,6 +213,7 @@ impl EvalHeap {
 pub enum EvalValue {
     Void,
     Int(i64),
+    Real(f64),
     Bool(bool),
     String(String),
     // used for non-primitive types
@@ -71,6 +221,10 @@ pub enum EvalValue {
     // used to return in
     // the eval rec function
     Return(Box<EvalValue>),
+    // used to break out of or skip
+    // the current iteration of loops
+    Break,
+    Continue,
 }
 
 impl EvalValue {
@@ -82,6 +236,10 @@ impl EvalValue {
         EvalValue::Int(val)
     }
 
+    fn real(val: f64) -> EvalValue {
+        EvalValue::Real(val)
+    }
+
     fn bool(val: bool) -> EvalValue {
         EvalValue::Bool(val)
     }
@@ -94,10 +252,21 @@ impl EvalValue {
         match self {
             EvalValue::Void => "void".to_string(),
             EvalValue::Int(val) => val.to_string(),
+            // always keeps a decimal point, i.e. 3.0
+            EvalValue::Real(val) => format!("{:?}", val),
             EvalValue::Bool(val) => val.to_string(),
             EvalValue::String(val) => val.clone(),
             EvalValue::Object(_) => unreachable!(),
             EvalValue::Return(_) => unreachable!(),
+            EvalValue::Break => unreachable!(),
+            EvalValue::Continue => unreachable!(),
+        }
+    }
+
+    fn is_control_flow(&self) -> bool {
+        match self {
+            EvalValue::Return(_) | EvalValue::Break | EvalValue::Continue => true,
+            _ => false,
         }
     }
 
@@ -109,6 +278,29 @@ impl EvalValue {
         val.clone()
     }
 
+    fn force_get_real(&self) -> f64 {
+        // ints are widened when used as reals
+        match self {
+            EvalValue::Real(val) => *val,
+            EvalValue::Int(val) => *val as f64,
+            _ => unreachable!(),
+        }
+    }
+
+    fn is_real(&self) -> bool {
+        match self {
+            EvalValue::Real(_) => true,
+            _ => false,
+        }
+    }
+
+    fn widen(self, target: &TypeKind) -> EvalValue {
+        match (target, &self) {
+            (TypeKind::Real, EvalValue::Int(val)) => EvalValue::real(*val as f64),
+            _ => self,
+        }
+    }
+
     fn force_get_bool(&self) -> bool {
         let EvalValue::Bool(val) = self else {
             unreachable!()
@@ -124,10 +316,57 @@ impl EvalValue {
 
         val.clone()
     }
+
+    fn force_get_object(&self) -> Arc<Mutex<ObjectState>> {
+        let EvalValue::Object(val) = self else {
+            unreachable!()
+        };
+
+        val.clone()
+    }
+}
+
+fn eval_int_arithmetic(
+    lhs: i64,
+    op: &Operator,
+    rhs: i64,
+    span: Span,
+) -> Result<EvalValue, RuntimeError> {
+    let is_division = match op {
+        Operator::Division | Operator::IntegerDivision | Operator::Modulo => true,
+        _ => false,
+    };
+
+    if is_division && rhs == 0 {
+        let message = "Division by zero".to_string();
+        return Err(RuntimeError::new(message, span));
+    }
+
+    let val = match op {
+        Operator::Addition => lhs.checked_add(rhs),
+        Operator::Subtraction => lhs.checked_sub(rhs),
+        Operator::Multiplication => lhs.checked_mul(rhs),
+        Operator::Division | Operator::IntegerDivision => lhs.checked_div(rhs),
+        Operator::Modulo => lhs.checked_rem(rhs),
+        _ => unreachable!(),
+    };
+
+    match val {
+        Some(val) => Ok(EvalValue::int(val)),
+        None => {
+            let message = "Integer overflow".to_string();
+            Err(RuntimeError::new(message, span))
+        }
+    }
 }
 
-fn eval_binary_expr(lhs: EvalValue, op: &Operator, rhs: EvalValue) -> EvalValue {
-    match op {
+fn eval_binary_expr(
+    lhs: EvalValue,
+    op: &Operator,
+    rhs: EvalValue,
+    span: Span,
+) -> Result<EvalValue, RuntimeError> {
+    let val = match op {
         Operator::Addition => {
             if let EvalValue::String(lhs_val) = &lhs {
                 let rhs_val = rhs.to_string();
@@ -137,33 +376,80 @@ fn eval_binary_expr(lhs: EvalValue, op: &Operator, rhs: EvalValue) -> EvalValue
                 let lhs_val = lhs.to_string();
                 let val = format!("{}{}", lhs_val, rhs_val);
                 EvalValue::String(val)
+            } else if lhs.is_real() || rhs.is_real() {
+                let lhs = lhs.force_get_real();
+                let rhs = rhs.force_get_real();
+                EvalValue::real(lhs + rhs)
             } else {
                 // addition on integers,
                 // binder should enforce this
                 let lhs = lhs.force_get_int();
                 let rhs = rhs.force_get_int();
-                EvalValue::int(lhs + rhs)
+                eval_int_arithmetic(lhs, op, rhs, span)?
             }
         }
         Operator::Subtraction => {
-            // subtraction is only defined on integers
+            // subtraction is only defined on numbers
+            if lhs.is_real() || rhs.is_real() {
+                let lhs = lhs.force_get_real();
+                let rhs = rhs.force_get_real();
+                return Ok(EvalValue::real(lhs - rhs));
+            }
+
             let lhs = lhs.force_get_int();
             let rhs = rhs.force_get_int();
-            EvalValue::int(lhs - rhs)
+            eval_int_arithmetic(lhs, op, rhs, span)?
         }
         Operator::Multiplication => {
-            // multiplication is only defined on integers
+            // multiplication is only defined on numbers
+            if lhs.is_real() || rhs.is_real() {
+                let lhs = lhs.force_get_real();
+                let rhs = rhs.force_get_real();
+                return Ok(EvalValue::real(lhs * rhs));
+            }
+
             let lhs = lhs.force_get_int();
             let rhs = rhs.force_get_int();
-            EvalValue::int(lhs * rhs)
+            eval_int_arithmetic(lhs, op, rhs, span)?
         }
         Operator::Division => {
-            // division is only defined on integers
+            // division is only defined on numbers
+            if lhs.is_real() || rhs.is_real() {
+                let lhs = lhs.force_get_real();
+                let rhs = rhs.force_get_real();
+                return Ok(EvalValue::real(lhs / rhs));
+            }
+
             let lhs = lhs.force_get_int();
             let rhs = rhs.force_get_int();
-            EvalValue::int(lhs / rhs)
+            eval_int_arithmetic(lhs, op, rhs, span)?
+        }
+        Operator::IntegerDivision => {
+            // only defined on integers
+            let lhs = lhs.force_get_int();
+            let rhs = rhs.force_get_int();
+            eval_int_arithmetic(lhs, op, rhs, span)?
+        }
+        Operator::Modulo => {
+            // only defined on integers
+            let lhs = lhs.force_get_int();
+            let rhs = rhs.force_get_int();
+            eval_int_arithmetic(lhs, op, rhs, span)?
+        }
+        Operator::And | Operator::Or => {
+            // lhs didn't short circuit,
+            // so the result is the rhs
+            let rhs = rhs.force_get_bool();
+            EvalValue::bool(rhs)
         }
         Operator::Equality => {
+            // ints and reals can be compared
+            if lhs.is_real() || rhs.is_real() {
+                let lhs = lhs.force_get_real();
+                let rhs = rhs.force_get_real();
+                return Ok(EvalValue::bool(lhs == rhs));
+            }
+
             // check if same variant
             if mem::discriminant(&lhs) != mem::discriminant(&rhs) {
                 unreachable!();
@@ -175,6 +461,7 @@ fn eval_binary_expr(lhs: EvalValue, op: &Operator, rhs: EvalValue) -> EvalValue
                     let rhs = rhs.force_get_int();
                     EvalValue::Bool(rhs == lhs)
                 }
+                EvalValue::Real(_) => unreachable!(),
                 EvalValue::Bool(lhs) => {
                     let rhs = rhs.force_get_bool();
                     EvalValue::Bool(rhs == lhs)
@@ -183,43 +470,168 @@ fn eval_binary_expr(lhs: EvalValue, op: &Operator, rhs: EvalValue) -> EvalValue
                     let rhs = rhs.force_get_string();
                     EvalValue::Bool(rhs == lhs)
                 }
-                EvalValue::Object(_) => unreachable!(),
+                // objects are only equal to themselves
+                EvalValue::Object(lhs) => {
+                    let rhs = rhs.force_get_object();
+                    EvalValue::Bool(Arc::ptr_eq(&lhs, &rhs))
+                }
                 EvalValue::Return(_) => unreachable!(),
+                EvalValue::Break => unreachable!(),
+                EvalValue::Continue => unreachable!(),
             }
         }
-        Operator::LesserThan => {
-            // rhs and lhs are ints
-            let lhs = lhs.force_get_int();
-            let rhs = rhs.force_get_int();
-            EvalValue::bool(lhs < rhs)
+        Operator::Inequality => {
+            let equal = eval_binary_expr(lhs, &Operator::Equality, rhs, span)?.force_get_bool();
+            EvalValue::bool(!equal)
         }
-        Operator::GreaterThan => {
-            // rhs and lhs are ints
-            let lhs = lhs.force_get_int();
-            let rhs = rhs.force_get_int();
-            EvalValue::bool(lhs > rhs)
+        Operator::LesserThan
+        | Operator::GreaterThan
+        | Operator::LesserThanOrEqual
+        | Operator::GreaterThanOrEqual => {
+            // rhs and lhs are either numbers or strings
+            let ordering = if let EvalValue::String(lhs) = &lhs {
+                lhs.partial_cmp(&rhs.force_get_string())
+            } else if lhs.is_real() || rhs.is_real() {
+                lhs.force_get_real().partial_cmp(&rhs.force_get_real())
+            } else {
+                lhs.force_get_int().partial_cmp(&rhs.force_get_int())
+            };
+
+            // nan isn't ordered, all comparisons are false
+            let Some(ordering) = ordering else {
+                return Ok(EvalValue::bool(false));
+            };
+
+            let val = match op {
+                Operator::LesserThan => ordering == Ordering::Less,
+                Operator::GreaterThan => ordering == Ordering::Greater,
+                Operator::LesserThanOrEqual => ordering != Ordering::Greater,
+                _ => ordering != Ordering::Less,
+            };
+
+            EvalValue::bool(val)
         }
         _ => {
             unreachable!("Not a binary operator")
         }
-    }
+    };
+
+    Ok(val)
 }
 
-fn eval_unary_expr(rhs_val: EvalValue, op: &Operator) -> EvalValue {
-    match op {
+fn eval_unary_expr(
+    rhs_val: EvalValue,
+    op: &Operator,
+    span: Span,
+) -> Result<EvalValue, RuntimeError> {
+    let val = match op {
         Operator::Not => {
             // only defined on bools
             let rhs = rhs_val.force_get_bool();
             EvalValue::Bool(!rhs)
         }
         Operator::Subtraction => {
-            // only defined on ints
+            // only defined on numbers
+            if let EvalValue::Real(rhs) = rhs_val {
+                return Ok(EvalValue::Real(-rhs));
+            }
+
             let rhs = rhs_val.force_get_int();
-            EvalValue::Int(-rhs)
+            eval_int_arithmetic(0, op, rhs, span)?
         }
         _ => {
             unreachable!("Not a unary operator")
         }
+    };
+
+    Ok(val)
+}
+
+fn get_field(
+    object: &EvalValue,
+    field: &FieldSymbol,
+    span: Span,
+) -> Result<EvalValue, RuntimeError> {
+    let object = object.force_get_object();
+    let object = object.lock().unwrap();
+    let ObjectState::Instance(state) = &*object else {
+        unreachable!()
+    };
+
+    match state.fields.get(&field.symbol_id) {
+        Some(v) => Ok(v.clone()),
+        None => {
+            let message = format!("Field '{}' is used before it's assigned", field.identifier);
+            Err(RuntimeError::new(message, span))
+        }
+    }
+}
+
+fn set_field(object: &EvalValue, field: &FieldSymbol, value: EvalValue) {
+    let object = object.force_get_object();
+    let mut object = object.lock().unwrap();
+    let ObjectState::Instance(state) = &mut *object else {
+        unreachable!()
+    };
+
+    state.fields.insert(field.symbol_id, value);
+}
+
+fn get_element(array: &EvalValue, index: i64, span: Span) -> Result<EvalValue, RuntimeError> {
+    let object = array.force_get_object();
+    let object = object.lock().unwrap();
+    match &*object {
+        ObjectState::Array(state) => state.get(index, span),
+        _ => unreachable!(),
+    }
+}
+
+fn set_element(
+    array: &EvalValue,
+    index: i64,
+    value: EvalValue,
+    span: Span,
+) -> Result<(), RuntimeError> {
+    let object = array.force_get_object();
+    let mut object = object.lock().unwrap();
+    match &mut *object {
+        ObjectState::Array(state) => state.set(index, value, span),
+        _ => unreachable!(),
+    }
+}
+
+// where the argument of a ref parameter came from, the
+// parameter's value is written back to it after the call
+enum EvalPlace {
+    Variable(VariableSymbol),
+    Field(EvalValue, FieldSymbol),
+    Element(EvalValue, i64),
+}
+
+async fn eval_ref_arg(
+    arg: &BoundNode,
+    info: Arc<Mutex<EvalInfo>>,
+    io: &mut impl EvalIO,
+) -> Result<(EvalValue, EvalPlace), RuntimeError> {
+    // the object and index are only evaluated once,
+    // the binder made sure the argument is assignable
+    match &arg.kind {
+        BoundNodeKind::ReferenceExpression(symbol) => {
+            let value = eval_rec(arg, info, io).await?;
+            Ok((value, EvalPlace::Variable(symbol.clone())))
+        }
+        BoundNodeKind::FieldExpression { base, field } => {
+            let base_value = eval_rec(&base, info, io).await?;
+            let value = get_field(&base_value, field, arg.span)?;
+            Ok((value, EvalPlace::Field(base_value, field.clone())))
+        }
+        BoundNodeKind::IndexExpression { base, index } => {
+            let base_value = eval_rec(&base, info.clone(), io).await?;
+            let index_value = eval_rec(&index, info, io).await?.force_get_int();
+            let value = get_element(&base_value, index_value, arg.span)?;
+            Ok((value, EvalPlace::Element(base_value, index_value)))
+        }
+        _ => unreachable!(),
     }
 }
 
@@ -228,33 +640,185 @@ async fn eval_call_args(
     args: &Box<Vec<BoundNode>>,
     info: Arc<Mutex<EvalInfo>>,
     io: &mut impl EvalIO,
-) {
-    let num_params = symbol.parameters.len();
-    for index in 0..num_params {
-        let param = &symbol.parameters[index];
-        let arg = &args[index];
+) -> Result<(Vec<EvalValue>, Vec<Option<EvalPlace>>), RuntimeError> {
+    // all arguments are evaluated in the caller's
+    // frame before any parameter is assigned
+    let mut values: Vec<EvalValue> = Vec::new();
+    let mut places: Vec<Option<EvalPlace>> = Vec::new();
+    for (param, arg) in symbol.parameters.iter().zip(args.iter()) {
+        let (value, place) = if param.is_ref {
+            let (value, place) = eval_ref_arg(arg, info.clone(), io).await?;
+            (value, Some(place))
+        } else {
+            (eval_rec(arg, info.clone(), io).await?, None)
+        };
+
+        values.push(value.widen(&param.param_type));
+        places.push(place);
+    }
+
+    Ok((values, places))
+}
+
+fn assign_call_args(
+    symbol: &FunctionSymbol,
+    values: Vec<EvalValue>,
+    info: &Arc<Mutex<EvalInfo>>,
+    span: Span,
+) -> Result<(), RuntimeError> {
+    let mut info = info.lock().unwrap();
+    for (param, value) in symbol.parameters.iter().zip(values) {
+        info.heap.assign_var(&param.symbol, value, span)?;
+    }
 
-        let symbol = &param.symbol;
-        let value = eval_rec(arg, info.clone(), io).await;
-        info.lock().unwrap().heap.assign_var(symbol, value);
+    Ok(())
+}
+
+fn read_ref_params(
+    symbol: &FunctionSymbol,
+    places: Vec<Option<EvalPlace>>,
+    info: &Arc<Mutex<EvalInfo>>,
+    span: Span,
+) -> Result<Vec<(EvalPlace, EvalValue)>, RuntimeError> {
+    let mut info = info.lock().unwrap();
+    let mut written: Vec<(EvalPlace, EvalValue)> = Vec::new();
+    for (param, place) in symbol.parameters.iter().zip(places) {
+        if let Some(place) = place {
+            let value = info.heap.get_var(&param.symbol, span)?;
+            written.push((place, value));
+        }
     }
+
+    Ok(written)
+}
+
+fn write_ref_args(
+    written: Vec<(EvalPlace, EvalValue)>,
+    info: &Arc<Mutex<EvalInfo>>,
+    span: Span,
+) -> Result<(), RuntimeError> {
+    // the callee's frame is gone, so variables
+    // are assigned in the caller's frame again
+    for (place, value) in written {
+        match place {
+            EvalPlace::Variable(symbol) => {
+                info.lock().unwrap().heap.assign_var(&symbol, value, span)?
+            }
+            EvalPlace::Field(object, field) => set_field(&object, &field, value),
+            EvalPlace::Element(array, index) => set_element(&array, index, value, span)?,
+        }
+    }
+
+    Ok(())
+}
+
+async fn eval_function_call(
+    symbol: &FunctionSymbol,
+    this: Option<(&VariableSymbol, EvalValue)>,
+    values: Vec<EvalValue>,
+    places: Vec<Option<EvalPlace>>,
+    info: Arc<Mutex<EvalInfo>>,
+    io: &mut impl EvalIO,
+    span: Span,
+) -> Result<EvalValue, RuntimeError> {
+    {
+        let mut info = info.lock().unwrap();
+        if info.heap.call_depth() >= info.max_call_depth {
+            let message = format!("Maximum call depth of {} exceeded", info.max_call_depth);
+            return Err(RuntimeError::new(message, span));
+        }
+
+        info.heap.push_frame(symbol.frame_depth);
+
+        // methods get the instance they're called on
+        if let Some((this_symbol, this_value)) = this {
+            info.heap.assign_var(this_symbol, this_value, span)?;
+        }
+    }
+
+    assign_call_args(symbol, values, &info, span)?;
+
+    let body = info.lock().unwrap().heap.get_func(symbol);
+    let ret_value = eval_rec(&body, info.clone(), io).await;
+
+    // ref parameters are read before their frame is popped
+    let written = read_ref_params(symbol, places, &info, span);
+    info.lock().unwrap().heap.pop_frame();
+
+    let written = written?;
+    let ret_value = match ret_value? {
+        EvalValue::Void => EvalValue::void(),
+        EvalValue::Return(ret_value) => ret_value.as_ref().clone().widen(&symbol.ret_type),
+        _ => unreachable!(),
+    };
+
+    write_ref_args(written, &info, span)?;
+
+    Ok(ret_value)
 }
 
 async fn eval_for_loop(
     iterator: &VariableSymbol,
-    lower_bound: usize,
-    upper_bound: usize,
+    lower_bound: &BoundNode,
+    upper_bound: &BoundNode,
+    step: Option<&BoundNode>,
     body: Arc<BoundNode>,
     info: Arc<Mutex<EvalInfo>>,
     io: &mut impl EvalIO,
-) -> EvalValue {
-    for index in lower_bound..upper_bound {
-        let index_val = EvalValue::Int(index as i64);
-        info.lock().unwrap().heap.assign_var(iterator, index_val);
-        eval_rec(&body, info.clone(), io).await;
+) -> Result<EvalValue, RuntimeError> {
+    // bounds are only evaluated once,
+    // before the first iteration
+    let lower_bound = eval_rec(lower_bound, info.clone(), io)
+        .await?
+        .force_get_int();
+    let upper_bound = eval_rec(upper_bound, info.clone(), io)
+        .await?
+        .force_get_int();
+    let step = match step {
+        Some(step) => {
+            let step_value = eval_rec(step, info.clone(), io).await?.force_get_int();
+            if step_value == 0 {
+                let message = "Loop step cannot be zero".to_string();
+                return Err(RuntimeError::new(message, step.span));
+            }
+
+            step_value
+        }
+        None => 1,
+    };
+
+    // None once stepping past the last index
+    // would overflow, the loop ends there
+    let mut next = Some(lower_bound);
+    while let Some(index) = next {
+        // the upper bound is exclusive in
+        // both directions
+        let in_range = if step > 0 {
+            index < upper_bound
+        } else {
+            index > upper_bound
+        };
+
+        if !in_range {
+            break;
+        }
+
+        let index_val = EvalValue::Int(index);
+        next = index.checked_add(step);
+        info.lock()
+            .unwrap()
+            .heap
+            .assign_var(iterator, index_val, body.span)?;
+
+        let val = eval_rec(&body, info.clone(), io).await?;
+        match val {
+            EvalValue::Break => break,
+            EvalValue::Return(_) => return Ok(val),
+            _ => {}
+        }
     }
 
-    EvalValue::void()
+    Ok(EvalValue::void())
 }
 
 async fn eval_while_loop(
@@ -262,9 +826,9 @@ async fn eval_while_loop(
     body: Arc<BoundNode>,
     info: Arc<Mutex<EvalInfo>>,
     io: &mut impl EvalIO,
-) -> EvalValue {
+) -> Result<EvalValue, RuntimeError> {
     loop {
-        let expr_eval = eval_rec(expr, info.clone(), io).await;
+        let expr_eval = eval_rec(expr, info.clone(), io).await?;
         let EvalValue::Bool(expr_eval) = expr_eval else {
             unreachable!()
         };
@@ -273,49 +837,191 @@ async fn eval_while_loop(
             break;
         }
 
-        eval_rec(&body, info.clone(), io).await;
+        let val = eval_rec(&body, info.clone(), io).await?;
+        match val {
+            EvalValue::Break => break,
+            EvalValue::Return(_) => return Ok(val),
+            _ => {}
+        }
+    }
+
+    Ok(EvalValue::void())
+}
+
+fn declare_hoisted(node: &BoundNode, info: &Arc<Mutex<EvalInfo>>) {
+    let mut info = info.lock().unwrap();
+    match &node.kind {
+        BoundNodeKind::FunctionDeclaration { symbol, block } => {
+            info.heap.declare_func(symbol, block.clone());
+        }
+        BoundNodeKind::ClassDeclaration {
+            symbol,
+            initializers,
+            methods,
+        } => {
+            info.heap.declare_class(symbol, initializers.clone());
+
+            for method in methods.iter() {
+                if let BoundNodeKind::FunctionDeclaration { symbol, block } = &method.kind {
+                    info.heap.declare_func(symbol, block.clone());
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+async fn eval_class_instantiation(
+    node: &BoundNode,
+    constructor: Option<&MethodSymbol>,
+    args: &Box<Vec<BoundNode>>,
+    info: Arc<Mutex<EvalInfo>>,
+    io: &mut impl EvalIO,
+) -> Result<EvalValue, RuntimeError> {
+    let TypeKind::Class(class_type) = &node.node_type else {
+        unreachable!()
+    };
+
+    // fields are initialized before the constructor runs,
+    // fields without an initializer are left unassigned
+    let initializers = info
+        .lock()
+        .unwrap()
+        .heap
+        .get_initializers(class_type.class_id);
+
+    let mut state = InstanceState::new(class_type.class_id);
+    for initializer in initializers.iter().flat_map(|i| i.iter()) {
+        let field = &initializer.field;
+        let value = eval_rec(&initializer.value, info.clone(), io)
+            .await?
+            .widen(&field.field_type);
+
+        state.fields.insert(field.symbol_id, value);
+    }
+
+    let object = ObjectState::Instance(state);
+    let object = EvalValue::Object(Arc::new(Mutex::new(object)));
+
+    if let Some(constructor) = constructor {
+        let (values, places) =
+            eval_call_args(&constructor.function, args, info.clone(), io).await?;
+        let this = Some((&constructor.this, object.clone()));
+        eval_function_call(
+            &constructor.function,
+            this,
+            values,
+            places,
+            info,
+            io,
+            node.span,
+        )
+        .await?;
     }
 
-    EvalValue::void()
+    Ok(object)
 }
 
 #[async_recursion]
-async fn eval_rec(node: &BoundNode, info: Arc<Mutex<EvalInfo>>, io: &mut impl EvalIO) -> EvalValue {
+async fn eval_rec(
+    node: &BoundNode,
+    info: Arc<Mutex<EvalInfo>>,
+    io: &mut impl EvalIO,
+) -> Result<EvalValue, RuntimeError> {
     let val = match &node.kind {
-        BoundNodeKind::Module { block } => eval_rec(&block, info, io).await,
+        // trees with errors are never evaluated
+        BoundNodeKind::Error => unreachable!(),
+        BoundNodeKind::Module { imports, block } => {
+            // imported modules run first, so their functions
+            // and globals are ready before they're used
+            for import in imports.iter() {
+                eval_rec(import, info.clone(), io).await?;
+            }
+
+            eval_rec(&block, info, io).await?
+        }
         BoundNodeKind::Block { children } => {
+            // functions are hoisted, so they can be
+            // called before their declaration is reached
             for child in children.iter() {
-                let val = eval_rec(child, info.clone(), io).await;
-                if let EvalValue::Return(_) = &val {
-                    return val;
+                declare_hoisted(child, &info);
+            }
+
+            for child in children.iter() {
+                let val = eval_rec(child, info.clone(), io).await?;
+                if val.is_control_flow() {
+                    return Ok(val);
                 }
             }
 
             EvalValue::void()
         }
         BoundNodeKind::AssignmentExpression { symbol, value } => {
-            let value = eval_rec(&value, info.clone(), io).await;
-            info.lock().unwrap().heap.assign_var(symbol, value.clone());
+            let value = eval_rec(&value, info.clone(), io)
+                .await?
+                .widen(&symbol.var_type);
+            info.lock()
+                .unwrap()
+                .heap
+                .assign_var(symbol, value.clone(), node.span)?;
 
             value
         }
+        BoundNodeKind::VariableDeclaration { .. } => {
+            // declared without a value, it's only
+            // stored once it's assigned
+            EvalValue::void()
+        }
         BoundNodeKind::ReferenceExpression(reference) => {
-            info.lock().unwrap().heap.get_var(&reference)
+            let value = info
+                .lock()
+                .unwrap()
+                .heap
+                .try_get_var(&reference, node.span)?;
+            match value {
+                Some(v) => v,
+                None => {
+                    let message = format!(
+                        "Variable '{}' is used before it's assigned",
+                        reference.identifier
+                    );
+                    return Err(RuntimeError::new(message, node.span));
+                }
+            }
         }
         BoundNodeKind::BinaryExpression { lhs, op, rhs } => {
-            let lhs_val = eval_rec(&lhs, info.clone(), io).await;
-            let rhs_val = eval_rec(&rhs, info, io).await;
-            eval_binary_expr(lhs_val, op, rhs_val)
+            let lhs_val = eval_rec(&lhs, info.clone(), io).await?;
+
+            // AND and OR short circuit, the rhs
+            // is only evaluated if it's needed
+            match op {
+                Operator::And if !lhs_val.force_get_bool() => return Ok(EvalValue::bool(false)),
+                Operator::Or if lhs_val.force_get_bool() => return Ok(EvalValue::bool(true)),
+                _ => {}
+            }
+
+            let rhs_val = eval_rec(&rhs, info, io).await?;
+            eval_binary_expr(lhs_val, op, rhs_val, node.span)?
         }
         BoundNodeKind::UnaryExpression { op, rhs } => {
-            let rhs_val = eval_rec(&rhs, info, io).await;
-            eval_unary_expr(rhs_val, op)
+            let rhs_val = eval_rec(&rhs, info, io).await?;
+            eval_unary_expr(rhs_val, op, node.span)?
         }
         BoundNodeKind::NumberLiteral(num) => EvalValue::int(*num),
+        BoundNodeKind::RealLiteral(num) => EvalValue::real(*num),
         BoundNodeKind::BooleanLiteral(val) => EvalValue::bool(*val),
         BoundNodeKind::StringLiteral(val) => EvalValue::string(val.clone()),
+        BoundNodeKind::InterpolatedString { parts } => {
+            let mut value = String::new();
+            for part in parts.iter() {
+                let part_value = eval_rec(part, info.clone(), io).await?;
+                value.push_str(&part_value.to_string());
+            }
+
+            EvalValue::string(value)
+        }
         BoundNodeKind::OutputStatement { expr } => {
-            let value = eval_rec(&expr, info, io).await;
+            let value = eval_rec(&expr, info, io).await?;
 
             let value = format!("{}\n", value.to_string());
             io.output(value).await;
@@ -325,7 +1031,7 @@ async fn eval_rec(node: &BoundNode, info: Arc<Mutex<EvalInfo>>, io: &mut impl Ev
         BoundNodeKind::ReturnStatement { expr } => {
             // create special return value
             let val = if let Some(expr) = expr {
-                eval_rec(&expr, info, io).await
+                eval_rec(&expr, info, io).await?
             } else {
                 EvalValue::void()
             };
@@ -338,45 +1044,72 @@ async fn eval_rec(node: &BoundNode, info: Arc<Mutex<EvalInfo>>, io: &mut impl Ev
             else_block,
         } => {
             let cond_value = eval_rec(&condition, info.clone(), io)
-                .await
+                .await?
                 .force_get_bool();
             let value = if cond_value {
-                eval_rec(&block, info, io).await
+                eval_rec(&block, info, io).await?
             } else if let Some(else_block) = else_block {
-                eval_rec(else_block, info, io).await
+                eval_rec(else_block, info, io).await?
             } else {
                 EvalValue::void()
             };
 
-            match &value {
-                EvalValue::Return(_) => value,
-                _ => EvalValue::void(),
+            if value.is_control_flow() {
+                value
+            } else {
+                EvalValue::void()
             }
         }
-        BoundNodeKind::FunctionDeclaration { symbol, block } => {
-            info.lock()
-                .unwrap()
-                .heap
-                .declare_func(symbol, block.clone());
+        BoundNodeKind::CaseStatement {
+            expr,
+            arms,
+            otherwise,
+        } => {
+            let expr_value = eval_rec(&expr, info.clone(), io).await?;
+
+            // the first arm with a matching value runs,
+            // the otherwise block if none of them match
+            let mut block = otherwise.as_deref();
+            for arm in arms.iter() {
+                let arm_value = eval_rec(&arm.value, info.clone(), io).await?;
+                let equal = eval_binary_expr(
+                    expr_value.clone(),
+                    &Operator::Equality,
+                    arm_value,
+                    arm.value.span,
+                )?
+                .force_get_bool();
 
+                if equal {
+                    block = Some(&arm.block);
+                    break;
+                }
+            }
+
+            let value = match block {
+                Some(block) => eval_rec(block, info, io).await?,
+                None => EvalValue::void(),
+            };
+
+            if value.is_control_flow() {
+                value
+            } else {
+                EvalValue::void()
+            }
+        }
+        BoundNodeKind::FunctionDeclaration { .. } | BoundNodeKind::ClassDeclaration { .. } => {
+            // already declared by the enclosing block
             EvalValue::void()
         }
         BoundNodeKind::BoundCallExpression { symbol, args } => {
-            eval_call_args(symbol, args, info.clone(), io).await;
+            let (values, places) = eval_call_args(symbol, args, info.clone(), io).await?;
 
+            // builtins don't take any parameters
             let builtin_eval = eval_builtin::try_eval_builtin(symbol, info.clone(), io).await;
             match builtin_eval {
                 Some(val) => val,
                 None => {
-                    // no need to clear arguments after executing the block
-                    let body = info.lock().unwrap().heap.get_func(symbol);
-                    let ret_value = eval_rec(&body, info.clone(), io).await;
-
-                    match ret_value {
-                        EvalValue::Void => EvalValue::void(),
-                        EvalValue::Return(ret_value) => ret_value.as_ref().clone(),
-                        _ => unreachable!(),
-                    }
+                    eval_function_call(symbol, None, values, places, info, io, node.span).await?
                 }
             }
         }
@@ -387,45 +1120,117 @@ async fn eval_rec(node: &BoundNode, info: Arc<Mutex<EvalInfo>>, io: &mut impl Ev
             EvalValue::Object(Arc::new(Mutex::new(object)))
         }
         BoundNodeKind::ObjectMemberExpression { base, next } => {
-            let base_value = eval_rec(&base, info.clone(), io).await;
+            let base_value = eval_rec(&base, info.clone(), io).await?;
 
             // next should either be a reference or a call ;D
             // values are also objects, but they don't hold state?
             match &next.kind {
                 BoundNodeKind::BoundCallExpression { symbol, args } => {
-                    eval_call_args(&symbol, &args, info.clone(), io).await;
-                    eval_type_method(base_value, symbol, info)
+                    // methods read their parameters from the current frame
+                    let (values, _) = eval_call_args(&symbol, &args, info.clone(), io).await?;
+                    assign_call_args(symbol, values, &info, next.span)?;
+                    eval_type_method(base_value, symbol, info, next.span)?
                 }
                 _ => unreachable!(),
             }
         }
+        BoundNodeKind::ClassInstantiation { constructor, args } => {
+            eval_class_instantiation(node, constructor.as_ref(), args, info, io).await?
+        }
+        BoundNodeKind::FieldExpression { base, field } => {
+            let base_value = eval_rec(&base, info, io).await?;
+            get_field(&base_value, field, node.span)?
+        }
+        BoundNodeKind::FieldAssignmentExpression { base, field, value } => {
+            let base_value = eval_rec(&base, info.clone(), io).await?;
+            let value = eval_rec(&value, info, io).await?.widen(&field.field_type);
+
+            set_field(&base_value, field, value.clone());
+            value
+        }
+        BoundNodeKind::MethodCallExpression { base, method, args } => {
+            let base_value = eval_rec(&base, info.clone(), io).await?;
+            let method = resolve_instance_method(&base_value, method, &info);
+            let (values, places) = eval_call_args(&method.function, args, info.clone(), io).await?;
+
+            let this = Some((&method.this, base_value));
+            eval_function_call(&method.function, this, values, places, info, io, node.span).await?
+        }
+        BoundNodeKind::ArrayLiteral { elements } => {
+            let element_type = match &node.node_type {
+                TypeKind::Array(element_type) => element_type.as_ref(),
+                _ => unreachable!(),
+            };
+
+            let mut values: Vec<EvalValue> = Vec::new();
+            for element in elements.iter() {
+                let value = eval_rec(element, info.clone(), io)
+                    .await?
+                    .widen(element_type);
+                values.push(value);
+            }
+
+            let object = ObjectState::Array(ArrayState::from_values(values));
+            EvalValue::Object(Arc::new(Mutex::new(object)))
+        }
+        BoundNodeKind::IndexExpression { base, index } => {
+            let base_value = eval_rec(&base, info.clone(), io).await?;
+            let index_value = eval_rec(&index, info, io).await?.force_get_int();
+            get_element(&base_value, index_value, node.span)?
+        }
+        BoundNodeKind::IndexAssignmentExpression { base, index, value } => {
+            let base_value = eval_rec(&base, info.clone(), io).await?;
+            let index_value = eval_rec(&index, info.clone(), io).await?.force_get_int();
+            let value = eval_rec(&value, info, io).await?.widen(&node.node_type);
+
+            set_element(&base_value, index_value, value.clone(), node.span)?;
+            value
+        }
         BoundNodeKind::ForLoop {
             iterator,
             lower_bound,
             upper_bound,
+            step,
             block,
         } => {
             eval_for_loop(
                 iterator,
-                lower_bound.clone(),
-                upper_bound.clone(),
+                &lower_bound,
+                &upper_bound,
+                step.as_deref(),
                 block.clone(),
                 info,
                 io,
             )
-            .await
+            .await?
         }
         BoundNodeKind::WhileLoop { expr, block } => {
-            eval_while_loop(expr, block.clone(), info, io).await
+            eval_while_loop(expr, block.clone(), info, io).await?
         }
+        BoundNodeKind::BreakStatement => EvalValue::Break,
+        BoundNodeKind::ContinueStatement => EvalValue::Continue,
     };
 
-    val
+    Ok(val)
 }
 
-pub async fn eval(root: &BoundNode, io: &mut impl EvalIO) {
+pub async fn eval(root: &BoundNode, io: &mut impl EvalIO, max_call_depth: usize) {
+    if root.contains_errors() {
+        let message = "Cannot run a program that contains errors\n".to_string();
+        io.output(message).await;
+        return;
+    }
+
     let heap = EvalHeap::new();
-    let info = EvalInfo { heap: heap };
+    let info = EvalInfo {
+        heap: heap,
+        max_call_depth: max_call_depth,
+    };
 
-    eval_rec(root, Arc::new(Mutex::new(info)), io).await;
+    // runtime errors stop the program and
+    // are reported through the regular output
+    if let Err(error) = eval_rec(root, Arc::new(Mutex::new(info)), io).await {
+        let message = format!("Runtime error: {}\n", error.format());
+        io.output(message).await;
+    }
 }
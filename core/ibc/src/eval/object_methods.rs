@@ -1,55 +1,57 @@
 use std::sync::{Arc, Mutex};
 
-use crate::analysis::binding::{
-    symbols::FunctionSymbol,
-    types::{ArrayState, CollectionState, ObjectState, QueueState, StackState},
+use crate::analysis::{
+    binding::{
+        symbols::{FunctionSymbol, MethodSymbol},
+        types::{ArrayState, CollectionState, ObjectState, QueueState, StackState},
+    },
+    span::Span,
 };
 
-use super::evaluator::{EvalInfo, EvalValue};
+use super::evaluator::{EvalInfo, EvalValue, RuntimeError};
 
 fn execute_array_method(
     state: &mut ArrayState,
     symbol: &FunctionSymbol,
     info: Arc<Mutex<EvalInfo>>,
-) -> EvalValue {
-    match symbol.identifier.as_str() {
+    span: Span,
+) -> Result<EvalValue, RuntimeError> {
+    let val = match symbol.identifier.as_str() {
         "push" => {
             let item = &symbol.parameters[0].symbol;
-            let item_value = info.lock().unwrap().heap.get_var(item);
+            let item_value = info.lock().unwrap().heap.get_var(item, span)?;
 
             state.internal.push(item_value);
             EvalValue::Void
         }
         "get" => {
             let index = &symbol.parameters[0].symbol;
-            let index_value = info.lock().unwrap().heap.get_var(index);
+            let index_value = info.lock().unwrap().heap.get_var(index, span)?;
 
             let index_value = match index_value {
-                EvalValue::Int(i) => i as usize,
+                EvalValue::Int(i) => i,
                 _ => unreachable!(),
             };
 
-            match state.internal.get(index_value) {
-                Some(v) => v.clone(),
-                None => {
-                    panic!("Runtime error");
-                }
-            }
+            state.get(index_value, span)?
         }
         "len" => {
             let length = state.internal.len() as i64;
             EvalValue::Int(length)
         }
         _ => unimplemented!(),
-    }
+    };
+
+    Ok(val)
 }
 
 fn execute_collection_method(
     state: &mut CollectionState,
     symbol: &FunctionSymbol,
     info: Arc<Mutex<EvalInfo>>,
-) -> EvalValue {
-    match symbol.identifier.as_str() {
+    span: Span,
+) -> Result<EvalValue, RuntimeError> {
+    let val = match symbol.identifier.as_str() {
         "hasNext" => {
             let index = state.head.clone();
             let res = state.internal.get(index).is_some();
@@ -63,7 +65,8 @@ fn execute_collection_method(
                     v.clone()
                 }
                 None => {
-                    panic!("Runtime error");
+                    let message = "Cannot get an item from an empty collection".to_string();
+                    return Err(RuntimeError::new(message, span));
                 }
             }
         }
@@ -73,7 +76,7 @@ fn execute_collection_method(
         }
         "addItem" => {
             let item = &symbol.parameters[0].symbol;
-            let item_value = info.lock().unwrap().heap.get_var(item);
+            let item_value = info.lock().unwrap().heap.get_var(item, span)?;
 
             state.internal.push(item_value);
             EvalValue::Void
@@ -83,70 +86,87 @@ fn execute_collection_method(
             EvalValue::Bool(res)
         }
         _ => unimplemented!(),
-    }
+    };
+
+    Ok(val)
 }
 
 fn execute_stack_method(
     state: &mut StackState,
     symbol: &FunctionSymbol,
     info: Arc<Mutex<EvalInfo>>,
-) -> EvalValue {
-    match symbol.identifier.as_str() {
+    span: Span,
+) -> Result<EvalValue, RuntimeError> {
+    let val = match symbol.identifier.as_str() {
         "push" => {
             let item = &symbol.parameters[0].symbol;
-            let item_value = info.lock().unwrap().heap.get_var(item);
+            let item_value = info.lock().unwrap().heap.get_var(item, span)?;
 
             state.internal.push(item_value);
             EvalValue::Void
         }
         "pop" => match state.internal.pop() {
             Some(v) => v,
-            None => panic!("Runtime error"),
+            None => {
+                let message = "Cannot pop from an empty stack".to_string();
+                return Err(RuntimeError::new(message, span));
+            }
         },
         "isEmpty" => {
             let res = state.internal.len() == 0;
             EvalValue::Bool(res)
         }
         _ => unimplemented!(),
-    }
+    };
+
+    Ok(val)
 }
 
 fn execute_queue_method(
     state: &mut QueueState,
     symbol: &FunctionSymbol,
     info: Arc<Mutex<EvalInfo>>,
-) -> EvalValue {
-    match symbol.identifier.as_str() {
+    span: Span,
+) -> Result<EvalValue, RuntimeError> {
+    let val = match symbol.identifier.as_str() {
         "enqueue" => {
             let item = &symbol.parameters[0].symbol;
-            let item_value = info.lock().unwrap().heap.get_var(item);
+            let item_value = info.lock().unwrap().heap.get_var(item, span)?;
 
             state.internal.insert(0, item_value);
             EvalValue::Void
         }
         "dequeue" => match state.internal.pop() {
             Some(v) => v,
-            None => panic!("Runtime error"),
+            None => {
+                let message = "Cannot dequeue from an empty queue".to_string();
+                return Err(RuntimeError::new(message, span));
+            }
         },
         "isEmpty" => {
             let res = state.internal.len() == 0;
             EvalValue::Bool(res)
         }
         _ => unimplemented!(),
-    }
+    };
+
+    Ok(val)
 }
 
 fn execute_object_method(
     state: Arc<Mutex<ObjectState>>,
     symbol: &FunctionSymbol,
     info: Arc<Mutex<EvalInfo>>,
-) -> EvalValue {
+    span: Span,
+) -> Result<EvalValue, RuntimeError> {
     let mut state = state.lock().unwrap();
     match &mut *state {
-        ObjectState::Array(state) => execute_array_method(state, symbol, info),
-        ObjectState::Collection(state) => execute_collection_method(state, symbol, info),
-        ObjectState::Stack(state) => execute_stack_method(state, symbol, info),
-        ObjectState::Queue(state) => execute_queue_method(state, symbol, info),
+        ObjectState::Array(state) => execute_array_method(state, symbol, info, span),
+        ObjectState::Collection(state) => execute_collection_method(state, symbol, info, span),
+        ObjectState::Stack(state) => execute_stack_method(state, symbol, info, span),
+        ObjectState::Queue(state) => execute_queue_method(state, symbol, info, span),
+        // methods of user classes are evaluated like functions
+        ObjectState::Instance(_) => unreachable!(),
     }
 }
 
@@ -154,9 +174,31 @@ pub fn eval_type_method(
     mut value: EvalValue,
     symbol: &FunctionSymbol,
     info: Arc<Mutex<EvalInfo>>,
-) -> EvalValue {
+    span: Span,
+) -> Result<EvalValue, RuntimeError> {
     match &mut value {
-        EvalValue::Object(state) => execute_object_method(state.clone(), symbol, info.clone()),
+        EvalValue::Object(state) => {
+            execute_object_method(state.clone(), symbol, info.clone(), span)
+        }
         _ => unimplemented!(),
     }
 }
+
+pub fn resolve_instance_method(
+    value: &EvalValue,
+    method: &MethodSymbol,
+    info: &Arc<Mutex<EvalInfo>>,
+) -> MethodSymbol {
+    // methods are dispatched on the class the object
+    // was created from, which can override them
+    let class_id = match value {
+        EvalValue::Object(state) => match &*state.lock().unwrap() {
+            ObjectState::Instance(instance) => instance.class_id,
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+
+    let identifier = &method.function.identifier;
+    info.lock().unwrap().heap.get_method(class_id, identifier)
+}
@@ -4,10 +4,10 @@ use std::{
 };
 
 use async_trait::async_trait;
-use eval::EvalIO;
-
-mod analysis;
-mod eval;
+use ibc::{
+    analysis,
+    eval::{evaluator, EvalIO},
+};
 
 struct IBEvaluator;
 
@@ -31,7 +31,10 @@ impl EvalIO for IBEvaluator {
 
 async fn parse_file() {
     let contents = fs::read_to_string("test.ib").unwrap();
-    let result = analysis::analyze(contents);
+
+    // imported modules are read from the same directory
+    let resolver = |module: &str| fs::read_to_string(format!("{}.ib", module)).ok();
+    let result = analysis::analyze(contents, Some("test".to_string()), &resolver);
     result.errors.report();
 
     let Some(root) = &result.root else {
@@ -39,10 +42,17 @@ async fn parse_file() {
     };
 
     // evaluate
-    eval::evaluator::eval(root, &mut IBEvaluator).await;
+    evaluator::eval(root, &mut IBEvaluator, evaluator::DEFAULT_MAX_CALL_DEPTH).await;
 }
 
-#[tokio::main]
-async fn main() {
-    parse_file().await;
+fn main() {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .thread_stack_size(evaluator::EVAL_STACK_SIZE)
+        .enable_all()
+        .build()
+        .unwrap();
+
+    // run on a worker thread, the main thread's stack can't be resized
+    let handle = runtime.spawn(parse_file());
+    runtime.block_on(handle).unwrap();
 }
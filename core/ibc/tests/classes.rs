@@ -0,0 +1,27 @@
+mod common;
+
+#[test]
+fn private_member_reported_on_declaring_class() {
+    let source = r#"
+class A
+    private secret: Int = 1
+
+    private function hidden() -> Int
+        return 2
+    end
+end
+
+class B extends A
+    function peek() -> Int
+        return this.secret + this.hidden()
+    end
+end
+"#;
+
+    let expected = vec![
+        "Member 'secret' of class 'A' is private".to_string(),
+        "Member 'hidden' of class 'A' is private".to_string(),
+    ];
+
+    assert_eq!(common::errors(source), expected);
+}
@@ -0,0 +1,14 @@
+use ibc::analysis;
+
+// analyzes a program without imports,
+// returns the messages of its errors
+pub fn errors(source: &str) -> Vec<String> {
+    let resolver = |_: &str| None;
+    let result = analysis::analyze(source.to_string(), None, &resolver);
+    result
+        .errors
+        .errors
+        .iter()
+        .map(|e| e.kind.format())
+        .collect()
+}
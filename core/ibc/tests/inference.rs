@@ -0,0 +1,31 @@
+mod common;
+
+#[test]
+fn return_type_from_field_of_inferred_param() {
+    let source = r#"
+class Box
+    v: Int = 4
+end
+
+function f(p)
+    return p.v
+end
+
+x: Int = f(new Box())
+"#;
+
+    assert_eq!(common::errors(source), Vec::<String>::new());
+}
+
+#[test]
+fn return_type_from_method_of_inferred_param() {
+    let source = r#"
+function size(a)
+    return a.len()
+end
+
+x: Int = size([1, 2, 3])
+"#;
+
+    assert_eq!(common::errors(source), Vec::<String>::new());
+}
@@ -0,0 +1,36 @@
+use ibc::analysis::{
+    error_bag::ErrorBag,
+    span::MAIN_FILE,
+    syntax::{self, lexer::TriviaKind, syntax_token::SyntaxKind},
+};
+
+mod common;
+
+#[test]
+fn generic_list_closed_before_assignment() {
+    let source = r#"
+x: Array<Int>= [1]
+y: Array<Array<Int>>= [[2]]
+b = 3 >= 2
+"#;
+
+    assert_eq!(common::errors(source), Vec::<String>::new());
+}
+
+#[test]
+fn comments_kept_on_parse_result() {
+    let source = "# only\n# comments\n";
+
+    let mut bag = ErrorBag::new();
+    let root = syntax::parse(source.to_string(), MAIN_FILE, &mut bag);
+
+    let SyntaxKind::Module { trivia, .. } = &root.kind else {
+        panic!("expected a module");
+    };
+
+    let comments: Vec<&TriviaKind> = trivia.iter().map(|t| &t.kind).collect();
+    assert!(matches!(comments[..], [
+        TriviaKind::LineComment(a),
+        TriviaKind::LineComment(b),
+    ] if a == " only" && b == " comments"));
+}
@@ -303,7 +303,9 @@ const Editor = () => {
                                 />
                             )}
                         </Stack>
-                        {tabs.length != 0 && <OutputBar code={code} />}
+                        {tabs.length != 0 && (
+                            <OutputBar code={code} fileId={currentFile?.id} />
+                        )}
                     </Stack>
                 </Stack>
                 <NewFileDialog
@@ -7,11 +7,13 @@ import {
 } from "@mui/material";
 import { FunctionComponent, useEffect, useRef, useState } from "react";
 import useWebSocket, { ReadyState } from "react-use-websocket";
+import { auth } from "services/firebase";
 
 const WS_URL = process.env.REACT_APP_WEBSOCKETS_URL;
 
 interface OutputProps {
     code: string;
+    fileId?: string;
 }
 
 enum WebSocketMessageKind {
@@ -26,14 +28,17 @@ interface WebSocketMessage {
     payload: string;
 }
 
-const OutputBar: FunctionComponent<OutputProps> = ({ code }) => {
+const OutputBar: FunctionComponent<OutputProps> = ({ code, fileId }) => {
     const [output, setOutput] = useState("");
     const [awaitingInput, setAwaitingInput] = useState(false);
     const [input, setInput] = useState("");
     const [running, setRunning] = useState(false);
 
     const [sockerUrl, setSocketUrl] = useState<string | null>(null);
-    const { sendMessage, lastMessage, readyState } = useWebSocket(sockerUrl);
+    const [protocols, setProtocols] = useState<string[]>([]);
+    const { sendMessage, lastMessage, readyState } = useWebSocket(sockerUrl, {
+        protocols: protocols,
+    });
 
     const onClick = async () => {
         setOutput("");
@@ -42,7 +47,20 @@ const OutputBar: FunctionComponent<OutputProps> = ({ code }) => {
             return;
         }
 
-        setSocketUrl(WS_URL);
+        // the token tells the server whose files can be imported,
+        // browsers can only send it as a protocol
+        const jwt = await auth.currentUser?.getIdToken();
+        if (jwt == undefined) {
+            console.error("Not signed in, can't run the program");
+            return;
+        }
+
+        setProtocols(["bearer", jwt]);
+
+        const params = new URLSearchParams();
+        if (fileId != undefined) params.set("id", fileId);
+
+        setSocketUrl(`${WS_URL}?${params}`);
     };
 
     useEffect(() => {
@@ -9,12 +9,13 @@ const ibLinter = linter(async (view) => {
     const ibDiagnostics = await runDiagnostics(currentFile);
 
     const diagnostics = ibDiagnostics.map((d) => {
+        // errors in imported files are shown at the top of the current file
         const diagnostic: Diagnostic = {
-            from: d.offset_start,
-            to: d.offset_end,
+            from: d.file == null ? d.offset_start : 0,
+            to: d.file == null ? d.offset_end : 0,
             severity: "error",
             source: "ibc",
-            message: d.message,
+            message: d.file == null ? d.message : `${d.file}: ${d.message}`,
         };
 
         return diagnostic;
@@ -5,6 +5,7 @@ export interface IBDiagnostic {
     message: string;
     offset_start: number;
     offset_end: number;
+    file: string | null;
 }
 
 export interface IBFile {